@@ -0,0 +1,21 @@
+use std::{fs, hint::black_box};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use js_workspace::workspace::Root;
+
+fn search(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("yarn.lock"), "").unwrap();
+    let deep = dir.path().join("packages/foo/src/components/button");
+    fs::create_dir_all(&deep).unwrap();
+
+    c.bench_function("root in cwd", |b| {
+        b.iter(|| Root::new(black_box(dir.path())).unwrap())
+    });
+    c.bench_function("root five levels up", |b| {
+        b.iter(|| Root::new(black_box(&deep)).unwrap())
+    });
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
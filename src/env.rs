@@ -1 +1,46 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    env,
+    hash::{BuildHasher, Hash},
+};
+
 pub(crate) const PREFERRED_WORKSPACE_MANAGER: &str = "PREFERRED_WORKSPACE_MANAGER";
+pub(crate) const NPM_CONFIG_USER_AGENT: &str = "npm_config_user_agent";
+/// The command the running manager was given, e.g. `exec` for `npx` or `dlx` for `pnpm dlx`.
+pub(crate) const NPM_COMMAND: &str = "npm_command";
+/// The path of the running manager's CLI script.
+pub(crate) const NPM_EXECPATH: &str = "npm_execpath";
+pub(crate) const JS_WORKSPACE_ROOT: &str = "JS_WORKSPACE_ROOT";
+pub(crate) const GITHUB_ACTIONS: &str = "GITHUB_ACTIONS";
+/// The `cache` input of `actions/setup-node`, exposed to the action as an environment variable.
+pub(crate) const INPUT_CACHE: &str = "INPUT_CACHE";
+
+/// A source of environment variables, so detection can be tested without mutating the
+/// process-global environment.
+pub trait Env {
+    /// The value of the variable `key`, or `None` if it's unset or not valid Unicode.
+    fn var(&self, key: &str) -> Option<String>;
+}
+
+/// The environment of the current process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessEnv;
+
+impl Env for ProcessEnv {
+    fn var(&self, key: &str) -> Option<String> {
+        env::var(key).ok()
+    }
+}
+
+/// A fixed set of variables, e.g. for tests.
+impl<K, V, S> Env for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: AsRef<str>,
+    S: BuildHasher,
+{
+    fn var(&self, key: &str) -> Option<String> {
+        self.get(key).map(|value| value.as_ref().to_string())
+    }
+}
@@ -1,2 +1,5 @@
-mod env;
+pub mod env;
+pub mod prelude;
+#[cfg(test)]
+mod test_utils;
 pub mod workspace;
@@ -0,0 +1,20 @@
+//! The commonly used types and traits, for a single glob import.
+//!
+//! ```no_run
+//! use js_workspace::prelude::*;
+//!
+//! fn manager() -> Result<Manager, RootError> {
+//!     let root = Root::builder()
+//!         .follow_symlinks(FollowSymlinks::Never)
+//!         .discover(".")?;
+//!     Ok(root.manager())
+//! }
+//! ```
+
+pub use crate::{
+    env::{Env, ProcessEnv},
+    workspace::{
+        CaseSensitivity, FollowSymlinks, Manager, Package, Root, RootBuilder, RootCache, YarnMode,
+        root::RootError,
+    },
+};
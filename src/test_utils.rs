@@ -0,0 +1,19 @@
+use std::{fs, path::Path};
+
+use tempfile::TempDir;
+
+/// Creates a temporary directory containing the given files, which may be nested.
+pub(crate) fn fixture(files: &[&str]) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for file in files {
+        write(dir.path(), file, "");
+    }
+    dir
+}
+
+/// Writes `contents` to `file` relative to `root`, creating parent directories.
+pub(crate) fn write(root: &Path, file: &str, contents: &str) {
+    let path = root.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
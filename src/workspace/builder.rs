@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use super::{
+    manager::Manager,
+    root::{Result, Root},
+    search::{CaseSensitivity, FollowSymlinks, Precedence, SearchOptions},
+};
+use crate::env::{Env, ProcessEnv};
+
+/// Configures how [`Root`] searches for a workspace. [`Root::new`] uses the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct RootBuilder {
+    search: SearchOptions,
+}
+
+impl RootBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the search may pass through symlinks. See [`FollowSymlinks`].
+    pub fn follow_symlinks(mut self, follow_symlinks: FollowSymlinks) -> Self {
+        self.search.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Sets whether manager file names must match exactly. See [`CaseSensitivity`].
+    pub fn case_sensitivity(mut self, case_sensitivity: CaseSensitivity) -> Self {
+        self.search.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets whether the search stops at the root of the enclosing git repository.
+    ///
+    /// Any directory with a `.git` directory or file counts, so a submodule's root is a
+    /// boundary too and the superproject is never searched.
+    pub fn stop_at_repo_boundary(mut self, stop: bool) -> Self {
+        self.search.stop_at_repo = stop;
+        self
+    }
+
+    /// Sets whether the search stops at the first directory containing a `Cargo.toml`, so a
+    /// JS workspace embedded in a Rust project, like a Tauri app, never escapes into an
+    /// enclosing workspace above it.
+    pub fn stop_at_cargo(mut self, stop: bool) -> Self {
+        self.search.stop_at_cargo = stop;
+        self
+    }
+
+    /// Sets which manager wins when several have files in the same directory. See
+    /// [`Precedence`].
+    pub fn precedence(mut self, precedence: Precedence) -> Self {
+        self.search.precedence = precedence;
+        self
+    }
+
+    /// Searches up from `cwd` for a workspace root using the configured options.
+    pub fn discover(&self, cwd: impl AsRef<Path>) -> Result<Root> {
+        self.discover_with_env(cwd, &ProcessEnv)
+    }
+
+    /// Like [`RootBuilder::discover`], reading environment overrides from `env`.
+    pub fn discover_with_env(&self, cwd: impl AsRef<Path>, env: &impl Env) -> Result<Root> {
+        if let Some(manager) = Manager::from_env_with(env)? {
+            return Root::search_manager(cwd, manager, &self.search);
+        }
+
+        Root::search(cwd, &self.search)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    #[cfg(unix)]
+    #[test]
+    fn discover_never_following_symlinks() {
+        let dir = fixture(&["outside/yarn.lock", "sandbox/package-lock.json"]);
+        // Canonicalize, as the temporary directory may itself be behind a symlink.
+        let base = dir.path().canonicalize().unwrap();
+        std::os::unix::fs::symlink(
+            base.join("outside/yarn.lock"),
+            base.join("sandbox/yarn.lock"),
+        )
+        .unwrap();
+
+        let root = Root::builder()
+            .follow_symlinks(FollowSymlinks::Never)
+            .discover(base.join("sandbox"))
+            .unwrap();
+        assert_eq!(root.manager(), Manager::Npm);
+        assert_eq!(root.path(), base.join("sandbox"));
+    }
+
+    #[test]
+    fn discover_ignoring_case() {
+        let dir = fixture(&["PNPM-workspace.yaml", "packages/foo/index.js"]);
+
+        let root = Root::builder()
+            .case_sensitivity(CaseSensitivity::Insensitive)
+            .discover(dir.path().join("packages/foo"))
+            .unwrap();
+        assert_eq!(root.manager(), Manager::Pnpm);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test_case(Precedence::SearchOrder, Manager::Yarn ; "search order")]
+    #[test_case(Precedence::ManifestFirst, Manager::Pnpm ; "manifest first")]
+    fn discover_with_precedence(precedence: Precedence, expected: Manager) {
+        let dir = fixture(&["pnpm-workspace.yaml", "yarn.lock"]);
+
+        let root = Root::builder()
+            .precedence(precedence)
+            .discover(dir.path())
+            .unwrap();
+        assert_eq!(root.manager(), expected);
+    }
+
+    #[test_case(false, Some(Manager::Yarn) ; "crossing")]
+    #[test_case(true, None ; "stopping")]
+    fn discover_under_cargo_project(stop_at_cargo: bool, expected: Option<Manager>) {
+        let dir = fixture(&["yarn.lock", "app/Cargo.toml", "app/web/src/main.ts"]);
+
+        let root = Root::builder()
+            .stop_at_cargo(stop_at_cargo)
+            .discover(dir.path().join("app/web/src"));
+        assert_eq!(root.ok().map(|root| root.manager()), expected);
+    }
+}
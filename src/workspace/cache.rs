@@ -0,0 +1,153 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use super::root::{Result, Root};
+
+/// What identifies a directory however its path is spelled: its device and inode.
+#[cfg(unix)]
+type DirKey = (u64, u64);
+/// What identifies a directory however its path is spelled: its canonical path.
+#[cfg(not(unix))]
+type DirKey = std::path::PathBuf;
+
+#[cfg(unix)]
+fn dir_key(dir: &Path) -> io::Result<DirKey> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(dir)?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_key(dir: &Path) -> io::Result<DirKey> {
+    fs::canonicalize(dir)
+}
+
+/// Remembers detected roots so that lookups from directories already walked by an
+/// earlier lookup don't search again.
+///
+/// Directories are told apart by identity rather than path, so a directory reached
+/// through a symlink or bind mount shares its entry with the same directory reached
+/// directly. Such a lookup returns the root as first found, with that lookup's path.
+#[derive(Debug, Default)]
+pub struct RootCache {
+    roots: HashMap<DirKey, Root>,
+}
+
+impl RootCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like [`Root::new`], reusing the result of any earlier lookup that walked `cwd`.
+    pub fn root(&mut self, cwd: impl AsRef<Path>) -> Result<Root> {
+        let cwd = cwd.as_ref();
+        // A `cwd` that doesn't exist can still have a root, it just can't be cached.
+        if let Ok(key) = dir_key(cwd)
+            && let Some(root) = self.roots.get(&key)
+        {
+            return Ok(root.clone());
+        }
+
+        let root = Root::new(cwd)?;
+        // Every directory the search passed through has the same root.
+        for dir in cwd
+            .ancestors()
+            .take_while(|dir| dir.starts_with(root.path()))
+        {
+            if let Ok(key) = dir_key(dir) {
+                self.roots.insert(key, root.clone());
+            }
+        }
+        Ok(root)
+    }
+}
+
+/// Whether `a` and `b` belong to the same workspace root. Paths outside of any workspace
+/// don't share one with anything.
+pub fn same_workspace(a: &Path, b: &Path) -> Result<bool> {
+    let mut cache = RootCache::new();
+    let (a, b) = match (cache.root(a), cache.root(b)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(error), _) | (_, Err(error)) if error.is_not_found() => return Ok(false),
+        (Err(error), _) | (_, Err(error)) => return Err(error),
+    };
+
+    Ok(a.manager() == b.manager() && fs::canonicalize(a.path())? == fs::canonicalize(b.path())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    #[test]
+    fn root_reuses_walked_directories() {
+        let dir = fixture(&["yarn.lock", "packages/foo/src/index.js"]);
+        let mut cache = RootCache::new();
+
+        let root = cache.root(dir.path().join("packages/foo/src")).unwrap();
+        assert_eq!(root.path(), dir.path());
+        assert_eq!(cache.roots.len(), 4);
+
+        let root = cache.root(dir.path().join("packages")).unwrap();
+        assert_eq!(root.path(), dir.path());
+        assert_eq!(cache.roots.len(), 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn root_shared_between_symlinked_paths() {
+        let dir = fixture(&["real/yarn.lock"]);
+        std::os::unix::fs::symlink(dir.path().join("real"), dir.path().join("a")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("real"), dir.path().join("b")).unwrap();
+        let mut cache = RootCache::new();
+
+        let root = cache.root(dir.path().join("a")).unwrap();
+        assert_eq!(root.path(), dir.path().join("a"));
+
+        // Were the cache keyed by path, `b` would be searched and found without a root.
+        fs::remove_file(dir.path().join("real/yarn.lock")).unwrap();
+        assert_eq!(cache.root(dir.path().join("b")).unwrap(), root);
+        assert_eq!(cache.roots.len(), 1);
+    }
+
+    #[test]
+    fn root_of_missing_directory() {
+        let dir = fixture(&["yarn.lock"]);
+        let mut cache = RootCache::new();
+
+        let root = cache.root(dir.path().join("missing/dir")).unwrap();
+        assert_eq!(root.path(), dir.path());
+        assert_eq!(cache.roots.len(), 1);
+    }
+
+    #[test]
+    fn same_workspace_within_one_root() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/foo/index.js",
+            "packages/bar/index.js",
+        ]);
+        let a = dir.path().join("packages/foo/index.js");
+        let b = dir.path().join("packages/bar/index.js");
+        assert!(same_workspace(&a, &b).unwrap());
+    }
+
+    #[test]
+    fn same_workspace_across_roots() {
+        let dir = fixture(&["web/yarn.lock", "web/index.js", "api/pnpm-workspace.yaml"]);
+        let a = dir.path().join("web/index.js");
+        let b = dir.path().join("api");
+        assert!(!same_workspace(&a, &b).unwrap());
+    }
+
+    #[test]
+    fn same_workspace_outside_any_root() {
+        let dir = fixture(&["web/yarn.lock", "web/index.js", "scratch/notes.txt"]);
+        let a = dir.path().join("web/index.js");
+        let b = dir.path().join("scratch/notes.txt");
+        assert!(!same_workspace(&a, &b).unwrap());
+    }
+}
@@ -0,0 +1,86 @@
+use std::{io, path::Path};
+
+use serde_json::Value;
+
+use super::{
+    json,
+    root::{Result, Root},
+};
+
+/// Detects the roots of every folder listed in an editor `.code-workspace` file.
+///
+/// Folders are resolved relative to the file, and folders that share a root only
+/// contribute it once, in the order they're listed.
+pub fn find_roots_from_code_workspace(path: &Path) -> Result<Vec<Root>> {
+    let workspace = json::read(path)?.ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+    let base = path.parent().unwrap_or(Path::new(""));
+
+    let folders = workspace
+        .get("folders")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|folder| folder.get("path").and_then(Value::as_str));
+
+    let mut roots = Vec::new();
+    for folder in folders {
+        let root = Root::new(base.join(folder))?;
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        test_utils::{fixture, write},
+        workspace::Manager,
+    };
+
+    #[test]
+    fn find_roots_from_code_workspace_deduplicates() {
+        let dir = fixture(&[
+            "web/yarn.lock",
+            "web/packages/app/index.js",
+            "api/pnpm-workspace.yaml",
+        ]);
+        write(
+            dir.path(),
+            "project.code-workspace",
+            r#"{
+                // Opened by the editor as a multi-root workspace.
+                "folders": [
+                    { "path": "web" },
+                    { "path": "api" },
+                    { "path": "web/packages/app", "name": "app" }
+                ]
+            }"#,
+        );
+
+        let roots = find_roots_from_code_workspace(&dir.path().join("project.code-workspace"));
+        let roots: Vec<_> = roots
+            .unwrap()
+            .into_iter()
+            .map(|root| (root.manager(), root.path().to_path_buf()))
+            .collect();
+        assert_eq!(
+            roots,
+            [
+                (Manager::Yarn, dir.path().join("web")),
+                (Manager::Pnpm, dir.path().join("api")),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_roots_from_missing_code_workspace() {
+        let dir = fixture(&[]);
+        let error = find_roots_from_code_workspace(&dir.path().join("missing.code-workspace"));
+        assert!(error.unwrap_err().is_not_found());
+    }
+}
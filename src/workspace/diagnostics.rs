@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+
+use super::{
+    manager::{Manager, SEARCH_ORDER},
+    root::{Result, Root},
+};
+
+/// Every manager file at a workspace root, for doctor-style tooling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceDiagnostics {
+    /// Each manager file or lockfile present at the root, in [`SEARCH_ORDER`].
+    pub found: Vec<(Manager, PathBuf)>,
+    /// The manager that detection selects.
+    pub selected: Manager,
+    /// Whether files of more than one manager are present.
+    pub conflicts: bool,
+}
+
+/// The tools layered in a workspace, e.g. turbo running tasks on top of pnpm, with
+/// changesets for versioning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceTooling {
+    /// The orchestrator running tasks across packages: Lerna or Rush, the only task
+    /// runners that are a [`Manager`]. Turbo isn't one, so it's never reported here; see
+    /// `has_turbo` instead.
+    pub task_runner: Option<Manager>,
+    /// Whether turbo runs the tasks, as [`Root::has_turbo`] finds it.
+    pub has_turbo: bool,
+    /// The manager that installs packages, as [`Root::underlying_manager`] finds it.
+    pub package_manager: Manager,
+    /// Whether there's a `.changeset` folder for changesets.
+    pub has_changesets: bool,
+}
+
+impl Root {
+    /// Detects the root for `cwd` as [`Root::new`] does and reports every manager file
+    /// found there, without touching anything.
+    pub fn diagnostics(cwd: impl AsRef<Path>) -> Result<WorkspaceDiagnostics> {
+        let root = Root::new(cwd)?;
+
+        let mut found = Vec::new();
+        for &manager in SEARCH_ORDER {
+            let files = [Some(manager.primary_file()), manager.lockfile()];
+            let mut files: Vec<_> = files.into_iter().flatten().collect();
+            files.dedup();
+            for file in files {
+                let path = root.path().join(file);
+                if path.try_exists()? {
+                    found.push((manager, path));
+                }
+            }
+        }
+
+        let conflicts = found.iter().any(|&(manager, _)| manager != found[0].0);
+        Ok(WorkspaceDiagnostics {
+            found,
+            selected: root.manager(),
+            conflicts,
+        })
+    }
+
+    /// Detects the tools layered in this workspace, for doctor-style tooling.
+    ///
+    /// An orchestrator's package manager is the one it pins (see
+    /// [`Root::orchestrator_npm_client`]), or else the one whose lockfile is present,
+    /// defaulting to npm.
+    pub fn tooling(&self) -> Result<WorkspaceTooling> {
+        let package_manager = match self.orchestrator_npm_client()? {
+            Some(manager) => Some(manager),
+            None => self.underlying_manager()?,
+        };
+        Ok(WorkspaceTooling {
+            task_runner: Some(self.manager()).filter(Manager::is_orchestrator),
+            has_turbo: self.has_turbo()?,
+            package_manager: package_manager.unwrap_or(Manager::Npm),
+            has_changesets: self.path().join(".changeset").try_exists()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test]
+    fn diagnostics_with_conflicting_files() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "pnpm-workspace.yaml",
+            "pnpm-lock.yaml",
+            "packages/foo/index.js",
+        ]);
+
+        let diagnostics = Root::diagnostics(dir.path().join("packages/foo")).unwrap();
+        assert_eq!(
+            diagnostics,
+            WorkspaceDiagnostics {
+                found: vec![
+                    (Manager::Yarn, dir.path().join("yarn.lock")),
+                    (Manager::Pnpm, dir.path().join("pnpm-workspace.yaml")),
+                    (Manager::Pnpm, dir.path().join("pnpm-lock.yaml")),
+                ],
+                selected: Manager::Yarn,
+                conflicts: true,
+            }
+        );
+    }
+
+    #[test]
+    fn diagnostics_without_conflicts() {
+        let dir = fixture(&["pnpm-workspace.yaml", "pnpm-lock.yaml"]);
+
+        let diagnostics = Root::diagnostics(dir.path()).unwrap();
+        assert_eq!(diagnostics.selected, Manager::Pnpm);
+        assert_eq!(diagnostics.found.len(), 2);
+        assert!(!diagnostics.conflicts);
+    }
+
+    #[test]
+    fn tooling_turbo_pnpm_changesets() {
+        let dir = fixture(&[
+            "pnpm-workspace.yaml",
+            "pnpm-lock.yaml",
+            "turbo.json",
+            ".changeset/config.json",
+            "packages/foo/package.json",
+        ]);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.tooling().unwrap(),
+            WorkspaceTooling {
+                task_runner: None,
+                has_turbo: true,
+                package_manager: Manager::Pnpm,
+                has_changesets: true,
+            }
+        );
+    }
+
+    #[test]
+    fn tooling_lerna_yarn() {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), "lerna.json", r#"{ "version": "1.0.0" }"#);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.tooling().unwrap(),
+            WorkspaceTooling {
+                task_runner: Some(Manager::Lerna),
+                has_turbo: false,
+                package_manager: Manager::Yarn,
+                has_changesets: false,
+            }
+        );
+    }
+
+    #[test]
+    fn tooling_unknown_npm_client() {
+        let dir = fixture(&["pnpm-lock.yaml"]);
+        write(dir.path(), "lerna.json", r#"{ "npmClient": "cnpm" }"#);
+
+        let root = Root::new(dir.path()).unwrap();
+        let tooling = root.tooling().unwrap();
+        assert_eq!(tooling.task_runner, Some(Manager::Lerna));
+        assert_eq!(tooling.package_manager, Manager::Pnpm);
+    }
+}
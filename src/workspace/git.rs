@@ -0,0 +1,45 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The files in the index of the git repository containing `cwd`, whether or not they're
+/// in the working tree, or `None` if git can't tell, e.g. outside a repository or without
+/// git installed.
+///
+/// Paths are joined onto `cwd`'s own spelling of the repository root, so they compare
+/// equal to paths found by searching up from `cwd`.
+pub(crate) fn tracked_files(cwd: &Path) -> Option<HashSet<PathBuf>> {
+    // The path from cwd up to the root, like `../../`, rather than the absolute root,
+    // which git would spell with symlinks resolved.
+    let up = git(cwd, &["rev-parse", "--show-cdup"])?;
+    let mut root = cwd.to_path_buf();
+    for _ in Path::new(up.trim_end()).components() {
+        root.pop();
+    }
+
+    // `:/` lists the whole repository rather than just what's below cwd.
+    let files = git(cwd, &["ls-files", "-z", "--full-name", "--", ":/"])?;
+    Some(
+        files
+            .split('\0')
+            .filter(|file| !file.is_empty())
+            .map(|file| root.join(file))
+            .collect(),
+    )
+}
+
+/// The stdout of a successful `git` run in `dir`.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
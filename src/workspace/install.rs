@@ -0,0 +1,252 @@
+use std::{fs, io};
+
+use super::{
+    manager::Manager,
+    root::{Result, Root},
+    yarn::YarnMode,
+};
+
+fn command(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+impl Manager {
+    /// An install command that uses only what's already cached, without network access,
+    /// and fails rather than updating the lockfile.
+    ///
+    /// npm uses `npm ci`, which installs exactly what `package-lock.json` pins after
+    /// removing `node_modules`, unlike `npm install`, which may update the lockfile. Lerna
+    /// installs through npm. Rush has no offline flag, but `rush install` never updates
+    /// its lockfile. Yarn's flags are those of yarn classic; Berry enables offline
+    /// installs through its `enableOfflineMode` setting instead.
+    pub fn install_command_offline(&self) -> Vec<String> {
+        match self {
+            Manager::Yarn => command(&["yarn", "install", "--frozen-lockfile", "--offline"]),
+            Manager::Pnpm => command(&["pnpm", "install", "--frozen-lockfile", "--offline"]),
+            Manager::Rush => command(&["rush", "install"]),
+            Manager::Npm | Manager::Lerna => command(&["npm", "ci", "--offline"]),
+        }
+    }
+
+    /// An install command that fails rather than updating the lockfile, for reproducible
+    /// CI. `yarn_mode` picks between yarn classic's `--frozen-lockfile` and Berry's
+    /// `--immutable`, and is ignored by other managers. See [`Root::yarn_mode`].
+    ///
+    /// [`Root::yarn_mode`]: super::Root::yarn_mode
+    pub fn frozen_install_command(&self, yarn_mode: YarnMode) -> Vec<String> {
+        match (self, yarn_mode) {
+            (Manager::Yarn, YarnMode::Classic) => {
+                command(&["yarn", "install", "--frozen-lockfile"])
+            }
+            (Manager::Yarn, YarnMode::Berry) => command(&["yarn", "install", "--immutable"]),
+            (Manager::Pnpm, _) => command(&["pnpm", "install", "--frozen-lockfile"]),
+            (Manager::Rush, _) => command(&["rush", "install"]),
+            (Manager::Npm | Manager::Lerna, _) => command(&["npm", "ci"]),
+        }
+    }
+
+    /// A command that adds `packages` as dependencies, or as dev dependencies if `dev`.
+    ///
+    /// Run it from the root if `to_root`, or otherwise from the package to add them to.
+    /// Yarn classic and pnpm refuse to add to the root without `-W` and `-w` respectively,
+    /// while npm just needs to run there. Berry rejects `-W`, so `yarn_mode` decides
+    /// whether it's passed, and is ignored by other managers. Lerna adds through npm. Rush
+    /// has no root package, so `to_root` is ignored. See [`Root::yarn_mode`].
+    ///
+    /// [`Root::yarn_mode`]: super::Root::yarn_mode
+    pub fn add_command(
+        &self,
+        packages: &[&str],
+        dev: bool,
+        to_root: bool,
+        yarn_mode: YarnMode,
+    ) -> Vec<String> {
+        let mut args = match self {
+            Manager::Yarn => command(&["yarn", "add"]),
+            Manager::Pnpm => command(&["pnpm", "add"]),
+            Manager::Rush => command(&["rush", "add"]),
+            Manager::Npm | Manager::Lerna => command(&["npm", "install"]),
+        };
+
+        for package in packages {
+            if *self == Manager::Rush {
+                args.push(String::from("--package"));
+            }
+            args.push(package.to_string());
+        }
+
+        if dev {
+            args.push(String::from(match self {
+                Manager::Yarn | Manager::Pnpm => "-D",
+                Manager::Rush => "--dev",
+                Manager::Npm | Manager::Lerna => "--save-dev",
+            }));
+        }
+
+        if to_root {
+            match (self, yarn_mode) {
+                (Manager::Yarn, YarnMode::Classic) => args.push(String::from("-W")),
+                (Manager::Pnpm, _) => args.push(String::from("-w")),
+                (Manager::Yarn, YarnMode::Berry)
+                | (Manager::Rush | Manager::Npm | Manager::Lerna, _) => {}
+            }
+        }
+
+        args
+    }
+
+    /// The paths, relative to the root, that an install creates and a clean install
+    /// removes first. Global stores and caches are left alone.
+    ///
+    /// Berry's Plug'n'Play loader and install state are included, and Rush installs into
+    /// `common/temp`.
+    pub fn clean_paths(&self) -> Vec<&'static str> {
+        match self {
+            Manager::Yarn => vec![
+                "node_modules",
+                ".yarn/install-state.gz",
+                ".yarn/unplugged",
+                ".pnp.cjs",
+                ".pnp.loader.mjs",
+            ],
+            Manager::Rush => vec!["common/temp"],
+            Manager::Pnpm | Manager::Npm | Manager::Lerna => vec!["node_modules"],
+        }
+    }
+}
+
+impl Root {
+    /// Removes the manager's [`clean_paths`](Manager::clean_paths) under the root, ignoring
+    /// those that don't exist. A symlink is removed itself rather than what it points to,
+    /// so nothing outside the root is touched.
+    pub fn clean(&self) -> Result<()> {
+        for path in self.manager().clean_paths() {
+            let path = self.path().join(path);
+            let meta = match path.symlink_metadata() {
+                Ok(meta) => meta,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error.into()),
+            };
+            if meta.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    #[test_case(Manager::Yarn, &["yarn", "install", "--frozen-lockfile", "--offline"] ; "yarn")]
+    #[test_case(Manager::Pnpm, &["pnpm", "install", "--frozen-lockfile", "--offline"] ; "pnpm")]
+    #[test_case(Manager::Rush, &["rush", "install"] ; "rush")]
+    #[test_case(Manager::Npm, &["npm", "ci", "--offline"] ; "npm")]
+    #[test_case(Manager::Lerna, &["npm", "ci", "--offline"] ; "lerna")]
+    fn install_command_offline(given: Manager, expected: &[&str]) {
+        assert_eq!(given.install_command_offline(), expected);
+    }
+
+    #[test_case(Manager::Yarn, YarnMode::Classic, &["yarn", "install", "--frozen-lockfile"] ; "yarn classic")]
+    #[test_case(Manager::Yarn, YarnMode::Berry, &["yarn", "install", "--immutable"] ; "yarn berry")]
+    #[test_case(Manager::Pnpm, YarnMode::Berry, &["pnpm", "install", "--frozen-lockfile"] ; "pnpm")]
+    #[test_case(Manager::Rush, YarnMode::Classic, &["rush", "install"] ; "rush")]
+    #[test_case(Manager::Npm, YarnMode::Classic, &["npm", "ci"] ; "npm")]
+    #[test_case(Manager::Lerna, YarnMode::Classic, &["npm", "ci"] ; "lerna")]
+    fn frozen_install_command(given: Manager, yarn_mode: YarnMode, expected: &[&str]) {
+        assert_eq!(given.frozen_install_command(yarn_mode), expected);
+    }
+
+    #[test_case(Manager::Yarn, false, false, &["yarn", "add", "react", "react-dom"] ; "yarn package")]
+    #[test_case(Manager::Yarn, true, false, &["yarn", "add", "react", "react-dom", "-D"] ; "yarn package dev")]
+    #[test_case(Manager::Yarn, false, true, &["yarn", "add", "react", "react-dom", "-W"] ; "yarn root")]
+    #[test_case(Manager::Yarn, true, true, &["yarn", "add", "react", "react-dom", "-D", "-W"] ; "yarn root dev")]
+    #[test_case(Manager::Pnpm, false, false, &["pnpm", "add", "react", "react-dom"] ; "pnpm package")]
+    #[test_case(Manager::Pnpm, true, false, &["pnpm", "add", "react", "react-dom", "-D"] ; "pnpm package dev")]
+    #[test_case(Manager::Pnpm, false, true, &["pnpm", "add", "react", "react-dom", "-w"] ; "pnpm root")]
+    #[test_case(Manager::Pnpm, true, true, &["pnpm", "add", "react", "react-dom", "-D", "-w"] ; "pnpm root dev")]
+    #[test_case(Manager::Rush, false, false, &["rush", "add", "--package", "react", "--package", "react-dom"] ; "rush package")]
+    #[test_case(Manager::Rush, true, false, &["rush", "add", "--package", "react", "--package", "react-dom", "--dev"] ; "rush package dev")]
+    #[test_case(Manager::Rush, false, true, &["rush", "add", "--package", "react", "--package", "react-dom"] ; "rush root")]
+    #[test_case(Manager::Rush, true, true, &["rush", "add", "--package", "react", "--package", "react-dom", "--dev"] ; "rush root dev")]
+    #[test_case(Manager::Npm, false, false, &["npm", "install", "react", "react-dom"] ; "npm package")]
+    #[test_case(Manager::Npm, true, false, &["npm", "install", "react", "react-dom", "--save-dev"] ; "npm package dev")]
+    #[test_case(Manager::Npm, false, true, &["npm", "install", "react", "react-dom"] ; "npm root")]
+    #[test_case(Manager::Npm, true, true, &["npm", "install", "react", "react-dom", "--save-dev"] ; "npm root dev")]
+    #[test_case(Manager::Lerna, false, false, &["npm", "install", "react", "react-dom"] ; "lerna package")]
+    #[test_case(Manager::Lerna, true, true, &["npm", "install", "react", "react-dom", "--save-dev"] ; "lerna root dev")]
+    fn add_command(given: Manager, dev: bool, to_root: bool, expected: &[&str]) {
+        assert_eq!(
+            given.add_command(&["react", "react-dom"], dev, to_root, YarnMode::Classic),
+            expected
+        );
+    }
+
+    #[test_case(false, false, &["yarn", "add", "react", "react-dom"] ; "package")]
+    #[test_case(false, true, &["yarn", "add", "react", "react-dom"] ; "root")]
+    #[test_case(true, true, &["yarn", "add", "react", "react-dom", "-D"] ; "root dev")]
+    fn add_command_berry(dev: bool, to_root: bool, expected: &[&str]) {
+        assert_eq!(
+            Manager::Yarn.add_command(&["react", "react-dom"], dev, to_root, YarnMode::Berry),
+            expected
+        );
+    }
+
+    #[test_case(Manager::Yarn, &["node_modules", ".yarn/install-state.gz", ".yarn/unplugged", ".pnp.cjs", ".pnp.loader.mjs"] ; "yarn")]
+    #[test_case(Manager::Pnpm, &["node_modules"] ; "pnpm")]
+    #[test_case(Manager::Rush, &["common/temp"] ; "rush")]
+    #[test_case(Manager::Npm, &["node_modules"] ; "npm")]
+    #[test_case(Manager::Lerna, &["node_modules"] ; "lerna")]
+    fn clean_paths(given: Manager, expected: &[&str]) {
+        assert_eq!(given.clean_paths(), expected);
+    }
+
+    #[test]
+    fn clean() {
+        let dir = fixture(&[
+            "yarn.lock",
+            ".yarnrc.yml",
+            ".pnp.cjs",
+            ".yarn/install-state.gz",
+            ".yarn/cache/lodash.zip",
+            "node_modules/lodash/package.json",
+            "packages/foo/package.json",
+        ]);
+
+        let root = Root::new(dir.path()).unwrap();
+        root.clean().unwrap();
+
+        for removed in [".pnp.cjs", ".yarn/install-state.gz", "node_modules"] {
+            assert!(!dir.path().join(removed).exists(), "{removed}");
+        }
+        for kept in [
+            "yarn.lock",
+            ".yarnrc.yml",
+            ".yarn/cache/lodash.zip",
+            "packages/foo/package.json",
+        ] {
+            assert!(dir.path().join(kept).exists(), "{kept}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clean_leaves_symlink_targets_outside_root() {
+        let outside = fixture(&["lodash/package.json"]);
+        let dir = fixture(&["package-lock.json"]);
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("node_modules")).unwrap();
+
+        let root = Root::new(dir.path()).unwrap();
+        root.clean().unwrap();
+
+        assert!(dir.path().join("node_modules").symlink_metadata().is_err());
+        assert!(outside.path().join("lodash/package.json").exists());
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fs, io, path::Path};
+
+use serde_json::Value;
+
+use super::root::Result;
+
+/// Reads and parses the JSON file at `path`, or `None` if there isn't one.
+///
+/// Comments are permitted, since files like `rush.json` and `lerna.json` allow them.
+pub(crate) fn read(path: &Path) -> Result<Option<Value>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(parse_jsonc(&contents)?)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Parses JSON that may contain `//` and `/* */` comments.
+pub(crate) fn parse_jsonc(contents: &str) -> Result<Value> {
+    Ok(serde_json::from_str(&strip_comments(contents))?)
+}
+
+/// Removes comments outside of string literals, keeping newlines so that parse
+/// errors still point at the right line.
+fn strip_comments(contents: &str) -> String {
+    let mut stripped = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                stripped.push(c);
+                while let Some(c) = chars.next() {
+                    stripped.push(c);
+                    match c {
+                        '\\' => stripped.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push(c);
+                    }
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::workspace::root::RootError;
+
+    #[test_case(r#"{ "a": 1 }"#, json!({ "a": 1 }) ; "plain json")]
+    #[test_case("{\n  // line comment\n  \"a\": 1 // trailing\n}", json!({ "a": 1 }) ; "line comments")]
+    #[test_case("{ /* block */ \"a\": /* multi\nline */ 1 }", json!({ "a": 1 }) ; "block comments")]
+    #[test_case(r#"{ "url": "https://example.com" }"#, json!({ "url": "https://example.com" }) ; "line comment marker in string")]
+    #[test_case(r#"{ "glob": "packages/*/src/**" }"#, json!({ "glob": "packages/*/src/**" }) ; "block comment marker in string")]
+    #[test_case(r#"{ "quote": "say \"// hi\"" } // bye"#, json!({ "quote": "say \"// hi\"" }) ; "escaped quote in string")]
+    #[test_case(r#"{ "path": "C:\\" } // bye"#, json!({ "path": "C:\\" }) ; "escaped backslash at end of string")]
+    fn parse_jsonc(given: &str, expected: Value) {
+        assert_eq!(super::parse_jsonc(given).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_jsonc_keeps_error_lines() {
+        let error = super::parse_jsonc("{\n  /* a\n  b */\n  \"a\": }").unwrap_err();
+        let RootError::Json(error) = error else {
+            panic!("expected a JSON error, got {error:?}");
+        };
+        assert_eq!(error.line(), 4);
+    }
+}
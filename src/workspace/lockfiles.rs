@@ -0,0 +1,393 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    json,
+    manager::{Manager, SEARCH_ORDER},
+    root::{Result, Root},
+    walk::walk_files,
+    yaml,
+};
+
+/// Whether `path` is named like any manager's lockfile.
+pub(crate) fn is_lockfile(path: &Path) -> bool {
+    let file_name = path.file_name();
+    SEARCH_ORDER
+        .iter()
+        .filter_map(|manager| manager.lockfile())
+        .any(|lockfile| Some(lockfile.as_os_str()) == file_name)
+}
+
+/// How a workspace's lockfiles are laid out. See [`Root::lockfile_layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockfileLayout {
+    /// At most one lockfile, at the root, as a workspace should have, or for Rush only
+    /// those under `common/`.
+    Single,
+    /// Several lockfiles, or one below the root, listing all of them by path. Managers
+    /// ignore the lockfiles of nested packages during a workspace install, so these are
+    /// usually left over from before the package joined the workspace.
+    Multiple(Vec<PathBuf>),
+}
+
+impl Root {
+    /// Every lockfile at or below the root, sorted by path, including those of nested
+    /// packages. Dependencies inside `node_modules` are skipped.
+    pub fn all_lockfiles(&self) -> Result<Vec<PathBuf>> {
+        let mut lockfiles = Vec::new();
+        walk_files(self.path(), &mut |path| {
+            if is_lockfile(path) {
+                lockfiles.push(path.to_path_buf());
+            }
+        })?;
+        lockfiles.sort();
+        Ok(lockfiles)
+    }
+
+    /// Whether the workspace has a single lockfile at its root, or stray ones in nested
+    /// packages too, as found by [`Root::all_lockfiles`].
+    ///
+    /// Rush keeps its lockfiles under `common/`, e.g. `common/config/rush/pnpm-lock.yaml`
+    /// and copies in `common/temp`, so those count as none at all.
+    pub fn lockfile_layout(&self) -> Result<LockfileLayout> {
+        let mut lockfiles = self.all_lockfiles()?;
+        if self.manager() == Manager::Rush {
+            let common = self.path().join("common");
+            lockfiles.retain(|lockfile| !lockfile.starts_with(&common));
+        }
+        match lockfiles.as_slice() {
+            [] => Ok(LockfileLayout::Single),
+            [lockfile] if lockfile.parent() == Some(self.path()) => Ok(LockfileLayout::Single),
+            _ => Ok(LockfileLayout::Multiple(lockfiles)),
+        }
+    }
+
+    /// The package manager that installs dependencies for this root.
+    ///
+    /// For an orchestrator (see [`Manager::is_orchestrator`]), this is the manager whose
+    /// lockfile sits beside the orchestrator's file, or `None` if there's no lockfile. Any
+    /// other manager is its own underlying manager.
+    ///
+    /// Rush pins its manager with `pnpmVersion`, `npmVersion`, or `yarnVersion` in
+    /// `rush.json`, and keeps the lockfile in `common/config/rush`, as `pnpm-lock.yaml`,
+    /// `npm-shrinkwrap.json`, or `yarn.lock`.
+    pub fn underlying_manager(&self) -> Result<Option<Manager>> {
+        match self.manager() {
+            Manager::Rush => return self.rush_manager(),
+            Manager::Lerna => {}
+            manager => return Ok(Some(manager)),
+        }
+
+        for manager in SEARCH_ORDER {
+            if let Some(lockfile) = manager.lockfile()
+                && self.path().join(lockfile).try_exists()?
+            {
+                return Ok(Some(*manager));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The manager a Rush workspace installs with. See [`Root::underlying_manager`].
+    fn rush_manager(&self) -> Result<Option<Manager>> {
+        const VERSIONS: [(&str, Manager); 3] = [
+            ("pnpmVersion", Manager::Pnpm),
+            ("npmVersion", Manager::Npm),
+            ("yarnVersion", Manager::Yarn),
+        ];
+        const LOCKFILES: [(&str, Manager); 3] = [
+            ("pnpm-lock.yaml", Manager::Pnpm),
+            ("npm-shrinkwrap.json", Manager::Npm),
+            ("yarn.lock", Manager::Yarn),
+        ];
+
+        if let Some(rush) = json::read(&self.manager_file())?
+            && let Some((_, manager)) = VERSIONS.iter().find(|(key, _)| rush.get(key).is_some())
+        {
+            return Ok(Some(*manager));
+        }
+
+        let config = self.path().join("common/config/rush");
+        for (lockfile, manager) in LOCKFILES {
+            if config.join(lockfile).try_exists()? {
+                return Ok(Some(manager));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The manager an orchestrator is configured to install with, from `npmClient` in
+    /// `lerna.json` or else `cli.packageManager` in `nx.json`. `None` if neither pins one,
+    /// or if the pinned client isn't a [`Manager`], like `cnpm` or `bun`.
+    ///
+    /// Unlike [`Root::underlying_manager`], this doesn't depend on a lockfile having been
+    /// committed yet.
+    pub fn orchestrator_npm_client(&self) -> Result<Option<Manager>> {
+        let lerna = json::read(&self.path().join(Manager::Lerna.primary_file()))?;
+        let nx = json::read(&self.path().join("nx.json"))?;
+        let client = lerna
+            .as_ref()
+            .and_then(|lerna| lerna.get("npmClient"))
+            .or_else(|| nx.as_ref().and_then(|nx| nx.pointer("/cli/packageManager")))
+            .and_then(serde_json::Value::as_str);
+        Ok(client.and_then(|client| client.parse().ok()))
+    }
+
+    /// The format version of the manager's lockfile, e.g. `3` for npm's `lockfileVersion`,
+    /// `6.0` for pnpm's, or `8` for yarn Berry's `__metadata.version`. Yarn classic
+    /// lockfiles report `1`.
+    ///
+    /// `None` for managers without a lockfile, or when the lockfile or its version is missing.
+    pub fn lockfile_version(&self) -> Result<Option<String>> {
+        let Some(lockfile) = self.manager().lockfile() else {
+            return Ok(None);
+        };
+        let lockfile = self.path().join(lockfile);
+
+        match self.manager() {
+            Manager::Npm => {
+                let lock = json::read(&lockfile)?;
+                let version = lock.as_ref().and_then(|lock| lock.get("lockfileVersion"));
+                Ok(version.and_then(json_version))
+            }
+            Manager::Pnpm => {
+                let lock = yaml::read(&lockfile)?;
+                let version = lock.as_ref().and_then(|lock| lock.get("lockfileVersion"));
+                Ok(version.and_then(yaml_version))
+            }
+            Manager::Yarn => match fs::read_to_string(&lockfile) {
+                Ok(contents) => Ok(yarn_lockfile_version(&contents)),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Manager::Rush | Manager::Lerna => Ok(None),
+        }
+    }
+}
+
+fn json_version(version: &serde_json::Value) -> Option<String> {
+    match version {
+        serde_json::Value::String(version) => Some(version.clone()),
+        serde_json::Value::Number(version) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+fn yaml_version(version: &serde_yaml::Value) -> Option<String> {
+    match version {
+        serde_yaml::Value::String(version) => Some(version.clone()),
+        serde_yaml::Value::Number(version) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+/// Reads the version of a yarn lockfile without parsing all of it, since classic
+/// lockfiles aren't YAML.
+fn yarn_lockfile_version(contents: &str) -> Option<String> {
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        if let Some(version) = line.strip_prefix("# yarn lockfile v") {
+            return Some(version.trim().to_string());
+        }
+        if line == "__metadata:" {
+            return lines
+                .take_while(|line| line.starts_with(' '))
+                .find_map(|line| line.trim().strip_prefix("version:"))
+                .map(|version| version.trim().to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test]
+    fn all_lockfiles() {
+        let dir = fixture(&[
+            "package.json",
+            "package-lock.json",
+            "packages/a/package.json",
+            "packages/b/package.json",
+            "packages/b/package-lock.json",
+            "packages/c/yarn.lock",
+            "node_modules/foo/package-lock.json",
+        ]);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.all_lockfiles().unwrap(),
+            [
+                dir.path().join("package-lock.json"),
+                dir.path().join("packages/b/package-lock.json"),
+                dir.path().join("packages/c/yarn.lock"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lockfile_layout_single() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/a/package.json",
+            "node_modules/foo/yarn.lock",
+        ]);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.lockfile_layout().unwrap(), LockfileLayout::Single);
+    }
+
+    #[test]
+    fn lockfile_layout_multiple() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/a/package.json",
+            "packages/b/package-lock.json",
+        ]);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.lockfile_layout().unwrap(),
+            LockfileLayout::Multiple(vec![
+                dir.path().join("packages/b/package-lock.json"),
+                dir.path().join("yarn.lock"),
+            ])
+        );
+    }
+
+    #[test]
+    fn lockfile_layout_rush() {
+        let dir = fixture(&[
+            "rush.json",
+            "common/config/rush/pnpm-lock.yaml",
+            "common/config/subspaces/default/pnpm-lock.yaml",
+            "common/temp/pnpm-lock.yaml",
+            "apps/web/package.json",
+            "apps/web/.rush/temp/shrinkwrap-deps.json",
+        ]);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.manager(), Manager::Rush);
+        assert_eq!(root.lockfile_layout().unwrap(), LockfileLayout::Single);
+
+        write(dir.path(), "apps/web/package-lock.json", "{}");
+        assert_eq!(
+            root.lockfile_layout().unwrap(),
+            LockfileLayout::Multiple(vec![dir.path().join("apps/web/package-lock.json")])
+        );
+    }
+
+    #[test_case(
+        "package-lock.json",
+        r#"{ "name": "foo", "lockfileVersion": 3, "requires": true, "packages": {} }"#,
+        Some("3") ;
+        "npm"
+    )]
+    #[test_case(
+        "pnpm-lock.yaml",
+        "lockfileVersion: '6.0'\n\nsettings:\n  autoInstallPeers: true\n",
+        Some("6.0") ;
+        "pnpm"
+    )]
+    #[test_case(
+        "pnpm-lock.yaml",
+        "lockfileVersion: 5.4\n\nspecifiers: {}\n",
+        Some("5.4") ;
+        "pnpm unquoted"
+    )]
+    #[test_case(
+        "yarn.lock",
+        "# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.\n# yarn lockfile v1\n\n",
+        Some("1") ;
+        "yarn classic"
+    )]
+    #[test_case(
+        "yarn.lock",
+        "# This file is generated by running \"yarn install\"\n\n__metadata:\n  version: 8\n  cacheKey: 10c0\n",
+        Some("8") ;
+        "yarn berry"
+    )]
+    #[test_case("package-lock.json", r#"{ "name": "foo" }"#, None ; "without version")]
+    fn lockfile_version(lockfile: &str, contents: &str, expected: Option<&str>) {
+        let dir = fixture(&[]);
+        write(dir.path(), lockfile, contents);
+        if lockfile == "pnpm-lock.yaml" {
+            write(dir.path(), "pnpm-workspace.yaml", "");
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.lockfile_version().unwrap().as_deref(), expected);
+    }
+
+    #[test_case(&["lerna.json", "yarn.lock"], Some(Manager::Yarn) ; "lerna with yarn")]
+    #[test_case(&["lerna.json", "pnpm-lock.yaml"], Some(Manager::Pnpm) ; "lerna with pnpm")]
+    #[test_case(&["lerna.json"], None ; "lerna without lockfile")]
+    #[test_case(&["yarn.lock", "package-lock.json"], Some(Manager::Yarn) ; "not an orchestrator")]
+    fn underlying_manager(files: &[&str], expected: Option<Manager>) {
+        let dir = fixture(files);
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.underlying_manager().unwrap(), expected);
+    }
+
+    #[test_case(&[("lerna.json", r#"{ "npmClient": "pnpm" }"#)], Some(Manager::Pnpm) ; "lerna")]
+    #[test_case(&[("lerna.json", r#"{ "version": "1.0.0" }"#)], None ; "lerna without npm client")]
+    #[test_case(&[("lerna.json", "{}"), ("nx.json", r#"{ "cli": { "packageManager": "yarn" } }"#)], Some(Manager::Yarn) ; "nx")]
+    #[test_case(
+        &[("lerna.json", r#"{ "npmClient": "pnpm" }"#), ("nx.json", r#"{ "cli": { "packageManager": "yarn" } }"#)],
+        Some(Manager::Pnpm) ;
+        "lerna over nx"
+    )]
+    fn orchestrator_npm_client(files: &[(&str, &str)], expected: Option<Manager>) {
+        let dir = fixture(&["package-lock.json"]);
+        for (file, contents) in files {
+            write(dir.path(), file, contents);
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.orchestrator_npm_client().unwrap(), expected);
+    }
+
+    #[test]
+    fn orchestrator_npm_client_unknown() {
+        let dir = fixture(&[]);
+        write(dir.path(), "lerna.json", r#"{ "npmClient": "cnpm" }"#);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.orchestrator_npm_client().unwrap(), None);
+    }
+
+    #[test_case(r#"{ "rushVersion": "5.120.0", "pnpmVersion": "8.15.0" }"#, &[], Some(Manager::Pnpm) ; "pinned pnpm")]
+    #[test_case(r#"{ "rushVersion": "5.120.0", "npmVersion": "8.19.4" }"#, &[], Some(Manager::Npm) ; "pinned npm")]
+    #[test_case(r#"{ "rushVersion": "5.120.0", "yarnVersion": "1.22.19" }"#, &[], Some(Manager::Yarn) ; "pinned yarn")]
+    #[test_case(r#"{ "rushVersion": "5.120.0" }"#, &["common/config/rush/npm-shrinkwrap.json"], Some(Manager::Npm) ; "shrinkwrap")]
+    #[test_case(r#"{ "rushVersion": "5.120.0" }"#, &["common/config/rush/pnpm-lock.yaml"], Some(Manager::Pnpm) ; "pnpm lockfile")]
+    #[test_case(r#"{ "rushVersion": "5.120.0" }"#, &[], None ; "neither")]
+    fn underlying_manager_rush(rush_json: &str, files: &[&str], expected: Option<Manager>) {
+        let mut files = files.to_vec();
+        files.push("apps/web/package.json");
+        let dir = fixture(&files);
+        write(dir.path(), "rush.json", rush_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.manager(), Manager::Rush);
+        assert_eq!(root.underlying_manager().unwrap(), expected);
+    }
+
+    #[test]
+    fn lockfile_version_without_lockfile() {
+        let dir = fixture(&["rush.json"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.lockfile_version().unwrap(), None);
+
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.lockfile_version().unwrap(), None);
+    }
+}
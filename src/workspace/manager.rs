@@ -5,7 +5,8 @@ use std::{
     str::FromStr,
 };
 
-use crate::env::PREFERRED_WORKSPACE_MANAGER;
+use super::search::CaseSensitivity;
+use crate::env::{Env, PREFERRED_WORKSPACE_MANAGER, ProcessEnv};
 
 // DO NOT REORDER! This order determines the precedence of the files, which is
 // important for cases like lerna where lerna.json and e.g. yarn.lock may both exist.
@@ -17,15 +18,114 @@ pub(crate) const SEARCH_ORDER: &[Manager] = &[
     Manager::Npm,
 ];
 
+/// [`SEARCH_ORDER`] with every manager identified by a workspace manifest, rather than by
+/// a lockfile, moved ahead of those that are. See [`Precedence::ManifestFirst`].
+///
+/// [`Precedence::ManifestFirst`]: super::Precedence::ManifestFirst
+pub(crate) const MANIFEST_FIRST_ORDER: &[Manager] = &[
+    Manager::Lerna,
+    Manager::Rush,
+    Manager::Pnpm,
+    Manager::Yarn,
+    Manager::Npm,
+];
+
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
-#[error("Invalid manager: {0}")]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
+#[cfg_attr(
+    feature = "miette",
+    diagnostic(
+        code(js_workspace::invalid_manager),
+        help("valid managers are yarn, pnpm, rush, npm, and lerna")
+    )
+)]
+#[error("{}", describe_invalid_manager(.0))]
 pub struct ParseManagerError(String);
 
+/// Spells out an empty name, e.g. from a variable set to `""`, which would otherwise
+/// read like a missing word.
+fn describe_invalid_manager(name: &str) -> String {
+    if name.trim().is_empty() {
+        String::from("Invalid manager: manager name is empty")
+    } else {
+        format!("Invalid manager: {name}")
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
+#[cfg_attr(
+    feature = "miette",
+    diagnostic(
+        code(js_workspace::invalid_manager_file),
+        help("expected a lockfile or workspace file such as yarn.lock or pnpm-workspace.yaml")
+    )
+)]
 #[error("Invalid manager file: {0}")]
-pub struct InvalidFileError(PathBuf);
+pub struct InvalidFileError(pub(crate) PathBuf);
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
+pub enum CorepackSpecError {
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Manager(#[from] ParseManagerError),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::inexact_corepack_version),
+            help("pin an exact version in packageManager, e.g. pnpm@9.1.0")
+        )
+    )]
+    #[error("Corepack requires an exact version such as 1.2.3, not a range: {0}")]
+    InexactVersion(String),
+}
+
+/// Which versions [`Manager::from_corepack_spec_with`] accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionPolicy {
+    /// Only exact versions, as corepack itself requires.
+    #[default]
+    Exact,
+    /// Any version string, including ranges like `^9`.
+    AllowRanges,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum StrictFileError {
+    #[error(transparent)]
+    Invalid(#[from] InvalidFileError),
+    #[error("Manager file name is not valid UTF-8: {0}")]
+    NonUtf8(PathBuf),
+}
+
+/// The files to change when switching a workspace from one manager to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationPlan {
+    /// Files of the old manager that the new one doesn't use.
+    pub remove: Vec<&'static str>,
+    /// Files the new manager expects that the old one didn't have.
+    pub create: Vec<&'static str>,
+}
 
-#[derive(Debug, PartialEq, Eq)]
+/// Every fact about a manager at once, see [`Manager::info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagerInfo {
+    /// See [`Manager::display_name`].
+    pub display_name: &'static str,
+    /// See [`Manager::binary`].
+    pub binary: &'static str,
+    /// See [`Manager::primary_file`].
+    pub primary_file: &'static Path,
+    /// See [`Manager::all_files`].
+    pub all_files: Vec<&'static str>,
+    /// See [`Manager::is_orchestrator`].
+    pub is_orchestrator: bool,
+    /// See [`Manager::supports_workspaces`].
+    pub supports_workspaces: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Manager {
     Yarn,
     Pnpm,
@@ -34,6 +134,14 @@ pub enum Manager {
     Lerna,
 }
 
+/// Falls back to npm, which ships with Node.js and so is the one manager that
+/// every environment can be expected to have.
+impl Default for Manager {
+    fn default() -> Self {
+        Self::Npm
+    }
+}
+
 impl FromStr for Manager {
     type Err = ParseManagerError;
 
@@ -51,15 +159,93 @@ impl FromStr for Manager {
 
 impl Manager {
     pub fn from_env() -> Result<Option<Manager>, ParseManagerError> {
-        match env::var(PREFERRED_WORKSPACE_MANAGER) {
-            Ok(var) => Ok(Some(var.parse()?)),
-            Err(_err) => Ok(None), // TODO: Maybe add some logging here?
+        Self::from_env_with(&ProcessEnv)
+    }
+
+    /// Like [`Manager::from_env`], reading `PREFERRED_WORKSPACE_MANAGER` from `env`.
+    pub fn from_env_with(env: &impl Env) -> Result<Option<Manager>, ParseManagerError> {
+        // TODO: Maybe add some logging when it's unset?
+        env.var(PREFERRED_WORKSPACE_MANAGER)
+            .map(|var| var.parse())
+            .transpose()
+    }
+
+    /// Parses a corepack `packageManager` spec like `pnpm@8.6.0+sha512.abc`, returning
+    /// the manager and its version without the `+hash` suffix, if one was given.
+    ///
+    /// Like corepack, versions must be exact, except for yarn's `berry`, `stable`, and
+    /// `canonical` pseudo-versions. See [`Manager::from_corepack_spec_with`].
+    pub fn from_corepack_spec(spec: &str) -> Result<(Manager, Option<String>), CorepackSpecError> {
+        Self::from_corepack_spec_with(spec, VersionPolicy::Exact)
+    }
+
+    /// Like [`Manager::from_corepack_spec`], with a choice of which versions to accept.
+    pub fn from_corepack_spec_with(
+        spec: &str,
+        policy: VersionPolicy,
+    ) -> Result<(Manager, Option<String>), CorepackSpecError> {
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        };
+        let manager = name.parse()?;
+        let version = version
+            .map(|version| {
+                version
+                    .split_once('+')
+                    .map_or(version, |(version, _hash)| version)
+            })
+            .filter(|version| !version.is_empty());
+
+        match version {
+            Some(version)
+                if manager == Manager::Yarn && YARN_PSEUDO_VERSIONS.contains(&version) =>
+            {
+                Ok((manager, Some(version.to_string())))
+            }
+            Some(version) if policy == VersionPolicy::Exact && !is_exact_version(version) => {
+                Err(CorepackSpecError::InexactVersion(spec.to_string()))
+            }
+            version => Ok((manager, version.map(String::from))),
         }
     }
-}
 
-impl AsRef<Path> for Manager {
-    fn as_ref(&self) -> &Path {
+    /// The manager whose primary file `path` is named after, with `case_sensitivity`
+    /// deciding whether e.g. `Yarn.lock` counts.
+    pub fn from_file_name(
+        path: &Path,
+        case_sensitivity: CaseSensitivity,
+    ) -> Result<Self, InvalidFileError> {
+        let name = path.file_name();
+        SEARCH_ORDER
+            .iter()
+            .copied()
+            .find(|manager| {
+                let primary_file = manager.primary_file().as_os_str();
+                name.is_some_and(|name| {
+                    if case_sensitivity.ignores_case() {
+                        name.eq_ignore_ascii_case(primary_file)
+                    } else {
+                        name == primary_file
+                    }
+                })
+            })
+            .ok_or_else(|| InvalidFileError(path.to_path_buf()))
+    }
+
+    /// Like `TryFrom<&Path>`, but reports a file name that isn't valid UTF-8 as
+    /// [`StrictFileError::NonUtf8`] rather than as an unknown file.
+    pub fn try_from_strict(path: &Path) -> Result<Self, StrictFileError> {
+        match path.file_name() {
+            Some(name) if name.to_str().is_none() => {
+                Err(StrictFileError::NonUtf8(path.to_path_buf()))
+            }
+            _ => Ok(Self::try_from(path)?),
+        }
+    }
+
+    /// The canonical file whose presence identifies this manager's workspace root.
+    pub fn primary_file(&self) -> &'static Path {
         match self {
             Manager::Yarn => Path::new("yarn.lock"),
             Manager::Pnpm => Path::new("pnpm-workspace.yaml"),
@@ -68,23 +254,223 @@ impl AsRef<Path> for Manager {
             Manager::Lerna => Path::new("lerna.json"),
         }
     }
+
+    /// The lockfile this manager writes at the root, if it has one of its own.
+    pub fn lockfile(&self) -> Option<&'static Path> {
+        match self {
+            Manager::Yarn => Some(Path::new("yarn.lock")),
+            Manager::Pnpm => Some(Path::new("pnpm-lock.yaml")),
+            Manager::Npm => Some(Path::new("package-lock.json")),
+            Manager::Rush | Manager::Lerna => None,
+        }
+    }
+
+    /// Every file this manager keeps at the root: its manager file and lockfile.
+    pub fn all_files(&self) -> Vec<&'static str> {
+        match self {
+            Manager::Yarn => vec!["yarn.lock"],
+            Manager::Pnpm => vec!["pnpm-workspace.yaml", "pnpm-lock.yaml"],
+            Manager::Rush => vec!["rush.json"],
+            Manager::Npm => vec!["package-lock.json"],
+            Manager::Lerna => vec!["lerna.json"],
+        }
+    }
+
+    /// The files to remove and create at the root to migrate from one manager to another.
+    pub fn migration_plan(from: &Manager, to: &Manager) -> MigrationPlan {
+        let (from, to) = (from.all_files(), to.all_files());
+        MigrationPlan {
+            remove: from
+                .iter()
+                .copied()
+                .filter(|file| !to.contains(file))
+                .collect(),
+            create: to
+                .iter()
+                .copied()
+                .filter(|file| !from.contains(file))
+                .collect(),
+        }
+    }
+
+    /// Whether this manager orchestrates tasks on top of another package manager, like lerna
+    /// and rush, rather than installing packages itself. See [`Root::underlying_manager`].
+    ///
+    /// [`Root::underlying_manager`]: super::Root::underlying_manager
+    pub fn is_orchestrator(&self) -> bool {
+        matches!(self, Manager::Rush | Manager::Lerna)
+    }
+
+    /// The canonical lowercase name of this manager, as accepted by [`FromStr`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Manager::Yarn => "yarn",
+            Manager::Pnpm => "pnpm",
+            Manager::Rush => "rush",
+            Manager::Npm => "npm",
+            Manager::Lerna => "lerna",
+        }
+    }
+
+    /// The name of this manager as styled by its project, e.g. `pnpm` or `Yarn`, for
+    /// showing to users.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Manager::Yarn => "Yarn",
+            Manager::Pnpm => "pnpm",
+            Manager::Rush => "Rush",
+            Manager::Npm => "npm",
+            Manager::Lerna => "Lerna",
+        }
+    }
+
+    /// Whether this manager can manage several packages in one workspace. npm can since
+    /// version 7.
+    pub fn supports_workspaces(&self) -> bool {
+        match self {
+            Manager::Yarn | Manager::Pnpm | Manager::Rush | Manager::Npm | Manager::Lerna => true,
+        }
+    }
+
+    /// Every fact about this manager in one struct, e.g. for showing it in a UI.
+    pub fn info(&self) -> ManagerInfo {
+        ManagerInfo {
+            display_name: self.display_name(),
+            binary: self.binary(),
+            primary_file: self.primary_file(),
+            all_files: self.all_files(),
+            is_orchestrator: self.is_orchestrator(),
+            supports_workspaces: self.supports_workspaces(),
+        }
+    }
+
+    /// The canonical name of every manager in a stable order, e.g. for shell completion of
+    /// a `--manager` flag. Every candidate parses back with [`FromStr`].
+    pub fn completion_candidates() -> Vec<&'static str> {
+        [
+            Manager::Yarn,
+            Manager::Pnpm,
+            Manager::Rush,
+            Manager::Npm,
+            Manager::Lerna,
+        ]
+        .iter()
+        .map(Manager::name)
+        .collect()
+    }
+
+    /// The prefixes of the environment variables this manager reads its configuration from,
+    /// e.g. for forwarding or scrubbing them around a subprocess.
+    pub fn env_prefixes(&self) -> &'static [&'static str] {
+        match self {
+            Manager::Yarn => &["YARN_"],
+            Manager::Pnpm => &["PNPM_", "npm_config_"],
+            Manager::Rush => &["RUSH_"],
+            Manager::Npm => &["npm_", "npm_config_"],
+            Manager::Lerna => &["LERNA_", "npm_config_"],
+        }
+    }
+
+    /// The name of the executable used to invoke this manager.
+    pub fn binary(&self) -> &'static str {
+        match self {
+            Manager::Yarn => "yarn",
+            Manager::Pnpm => "pnpm",
+            Manager::Rush => "rush",
+            Manager::Npm => "npm",
+            Manager::Lerna => "lerna",
+        }
+    }
+
+    /// Removes duplicate managers, ordering the remainder by their [`SEARCH_ORDER`] precedence.
+    pub fn dedup_preserving_precedence(managers: &[Manager]) -> Vec<Manager> {
+        SEARCH_ORDER
+            .iter()
+            .filter(|manager| managers.contains(manager))
+            .copied()
+            .collect()
+    }
+
+    /// Whether the manager's binary resolves on `PATH`, without spawning it.
+    pub fn is_available(&self) -> bool {
+        env::var_os("PATH").is_some_and(|path| self.is_available_in(path))
+    }
+
+    /// Whether the manager's binary resolves on the given `PATH`-style search list.
+    pub fn is_available_in(&self, path: impl AsRef<OsStr>) -> bool {
+        env::split_paths(&path).any(|dir| is_executable(&dir.join(self.binary())))
+    }
+}
+
+/// The release channels yarn accepts in place of a version, all of which are Berry.
+pub(crate) const YARN_PSEUDO_VERSIONS: &[&str] = &["berry", "stable", "canonical"];
+
+/// Whether `version` is an exact semver version like `1.2.3` or `1.2.3-rc.1`.
+fn is_exact_version(version: &str) -> bool {
+    let (core, prerelease) = match version.split_once('-') {
+        Some((core, prerelease)) => (core, Some(prerelease)),
+        None => (version, None),
+    };
+    let numeric = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let parts: Vec<_> = core.split('.').collect();
+
+    parts.len() == 3
+        && parts.into_iter().all(numeric)
+        && prerelease.is_none_or(|prerelease| {
+            !prerelease.is_empty()
+                && prerelease
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-')
+        })
 }
 
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    ["exe", "cmd", "bat"]
+        .iter()
+        .any(|extension| path.with_extension(extension).is_file())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+impl AsRef<Path> for Manager {
+    fn as_ref(&self) -> &Path {
+        self.primary_file()
+    }
+}
+
+/// Matches the file name exactly. See [`Manager::from_file_name`] to ignore case.
 impl TryFrom<&Path> for Manager {
     type Error = InvalidFileError;
 
     fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        match path.file_name().and_then(OsStr::to_str) {
-            Some("yarn.lock") => Ok(Self::Yarn),
-            Some("pnpm-workspace.yaml") => Ok(Manager::Pnpm),
-            Some("rush.json") => Ok(Manager::Rush),
-            Some("package-lock.json") => Ok(Manager::Npm),
-            Some("lerna.json") => Ok(Manager::Lerna),
-            _ => Err(InvalidFileError(path.to_path_buf())),
-        }
+        Self::from_file_name(path, CaseSensitivity::Sensitive)
     }
 }
 
+/// Filters `entries` down to manager files, pairing each with its manager, e.g. for a
+/// directory listing a tool already has. Matching is as exact as `TryFrom<&Path>`.
+pub fn manager_files_in<'a>(
+    entries: impl Iterator<Item = &'a Path>,
+) -> impl Iterator<Item = (Manager, &'a Path)> {
+    entries.filter_map(|entry| {
+        Manager::try_from(entry)
+            .ok()
+            .map(|manager| (manager, entry))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -92,6 +478,27 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn manager_files_in_listing() {
+        let listing = [
+            Path::new("repo/package.json"),
+            Path::new("repo/yarn.lock"),
+            Path::new("repo/README.md"),
+            Path::new("repo/lerna.json"),
+            Path::new("repo/packages"),
+            Path::new("repo/Yarn.lock"),
+        ];
+
+        let actual: Vec<_> = manager_files_in(listing.into_iter()).collect();
+        assert_eq!(
+            actual,
+            vec![
+                (Manager::Yarn, Path::new("repo/yarn.lock")),
+                (Manager::Lerna, Path::new("repo/lerna.json")),
+            ]
+        );
+    }
+
     #[test_case("yarn", Ok(Manager::Yarn) ; "lowercase yarn")]
     #[test_case("YARN", Ok(Manager::Yarn) ; "uppercase yarn")]
     #[test_case("pnpm", Ok(Manager::Pnpm) ; "lowercase pnpm")]
@@ -109,6 +516,70 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test_case("", "Invalid manager: manager name is empty" ; "empty")]
+    #[test_case("   ", "Invalid manager: manager name is empty" ; "whitespace only")]
+    #[test_case("lolwut", "Invalid manager: lolwut" ; "invalid")]
+    fn parse_manager_error_message(given: &str, expected: &str) {
+        let error = given.parse::<Manager>().unwrap_err();
+        assert_eq!(error, ParseManagerError(given.to_string()));
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test_case("pnpm", Ok((Manager::Pnpm, None)) ; "name only")]
+    #[test_case("pnpm@8.6.0", Ok((Manager::Pnpm, Some(String::from("8.6.0")))) ; "name and version")]
+    #[test_case("yarn@4.1.0+sha512.abc123", Ok((Manager::Yarn, Some(String::from("4.1.0")))) ; "name version and hash")]
+    #[test_case("npm@", Ok((Manager::Npm, None)) ; "empty version")]
+    #[test_case("pnpm@9.0.0-rc.1", Ok((Manager::Pnpm, Some(String::from("9.0.0-rc.1")))) ; "prerelease version")]
+    #[test_case("lolwut@1.0.0", Err(CorepackSpecError::Manager(ParseManagerError(String::from("lolwut")))) ; "unknown manager")]
+    #[test_case("npm@^9", Err(CorepackSpecError::InexactVersion(String::from("npm@^9"))) ; "caret range")]
+    #[test_case("npm@9", Err(CorepackSpecError::InexactVersion(String::from("npm@9"))) ; "partial version")]
+    #[test_case("npm@latest", Err(CorepackSpecError::InexactVersion(String::from("npm@latest"))) ; "tag")]
+    #[test_case("yarn@berry", Ok((Manager::Yarn, Some(String::from("berry")))) ; "yarn berry")]
+    #[test_case("yarn@stable", Ok((Manager::Yarn, Some(String::from("stable")))) ; "yarn stable")]
+    #[test_case("npm@stable", Err(CorepackSpecError::InexactVersion(String::from("npm@stable"))) ; "pseudo version of other manager")]
+    fn from_corepack_spec(
+        given: &str,
+        expected: Result<(Manager, Option<String>), CorepackSpecError>,
+    ) {
+        assert_eq!(Manager::from_corepack_spec(given), expected);
+    }
+
+    #[test_case("npm@9.8.1", Ok((Manager::Npm, Some(String::from("9.8.1")))) ; "exact version")]
+    #[test_case("npm@^9", Ok((Manager::Npm, Some(String::from("^9")))) ; "caret range")]
+    #[test_case("pnpm@>=8 <9", Ok((Manager::Pnpm, Some(String::from(">=8 <9")))) ; "comparator range")]
+    fn from_corepack_spec_allowing_ranges(
+        given: &str,
+        expected: Result<(Manager, Option<String>), CorepackSpecError>,
+    ) {
+        let actual = Manager::from_corepack_spec_with(given, VersionPolicy::AllowRanges);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn default_is_npm() {
+        assert_eq!(Manager::default(), Manager::Npm);
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn diagnostics() {
+        use miette::Diagnostic;
+
+        let error = ParseManagerError(String::from("lolwut"));
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "js_workspace::invalid_manager"
+        );
+        assert!(error.help().unwrap().to_string().contains("pnpm"));
+
+        let error = InvalidFileError(PathBuf::from("invalid"));
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "js_workspace::invalid_manager_file"
+        );
+        assert!(error.help().is_some());
+    }
+
     #[test_case(Manager::Yarn, &Path::new("yarn.lock") ; "yarn")]
     #[test_case(Manager::Pnpm, &Path::new("pnpm-workspace.yaml") ; "pnpm")]
     #[test_case(Manager::Rush, &Path::new("rush.json") ; "rush")]
@@ -119,6 +590,160 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test_case(Manager::Yarn ; "yarn")]
+    #[test_case(Manager::Pnpm ; "pnpm")]
+    #[test_case(Manager::Rush ; "rush")]
+    #[test_case(Manager::Npm ; "npm")]
+    #[test_case(Manager::Lerna ; "lerna")]
+    fn primary_file_matches_as_ref(given: Manager) {
+        let expected: &Path = given.as_ref();
+        assert_eq!(given.primary_file(), expected);
+    }
+
+    #[test_case(Manager::Yarn, Some(Path::new("yarn.lock")) ; "yarn")]
+    #[test_case(Manager::Pnpm, Some(Path::new("pnpm-lock.yaml")) ; "pnpm")]
+    #[test_case(Manager::Rush, None ; "rush")]
+    #[test_case(Manager::Npm, Some(Path::new("package-lock.json")) ; "npm")]
+    #[test_case(Manager::Lerna, None ; "lerna")]
+    fn lockfile(given: Manager, expected: Option<&Path>) {
+        assert_eq!(given.lockfile(), expected);
+    }
+
+    #[test_case(Manager::Yarn, "yarn" ; "yarn")]
+    #[test_case(Manager::Pnpm, "pnpm" ; "pnpm")]
+    #[test_case(Manager::Rush, "rush" ; "rush")]
+    #[test_case(Manager::Npm, "npm" ; "npm")]
+    #[test_case(Manager::Lerna, "lerna" ; "lerna")]
+    fn binary(given: Manager, expected: &str) {
+        assert_eq!(given.binary(), expected);
+    }
+
+    #[test]
+    fn info() {
+        let manager = Manager::Pnpm;
+        assert_eq!(
+            manager.info(),
+            ManagerInfo {
+                display_name: "pnpm",
+                binary: manager.binary(),
+                primary_file: manager.primary_file(),
+                all_files: manager.all_files(),
+                is_orchestrator: manager.is_orchestrator(),
+                supports_workspaces: manager.supports_workspaces(),
+            }
+        );
+    }
+
+    #[test_case(Manager::Yarn, "Yarn" ; "yarn")]
+    #[test_case(Manager::Pnpm, "pnpm" ; "pnpm")]
+    #[test_case(Manager::Rush, "Rush" ; "rush")]
+    #[test_case(Manager::Npm, "npm" ; "npm")]
+    #[test_case(Manager::Lerna, "Lerna" ; "lerna")]
+    fn display_name_matches_name(given: Manager, expected: &str) {
+        assert_eq!(given.display_name(), expected);
+        assert!(given.display_name().eq_ignore_ascii_case(given.name()));
+    }
+
+    #[test_case(Manager::Yarn, false ; "yarn")]
+    #[test_case(Manager::Pnpm, false ; "pnpm")]
+    #[test_case(Manager::Rush, true ; "rush")]
+    #[test_case(Manager::Npm, false ; "npm")]
+    #[test_case(Manager::Lerna, true ; "lerna")]
+    fn is_orchestrator(given: Manager, expected: bool) {
+        assert_eq!(given.is_orchestrator(), expected);
+    }
+
+    #[test_case(Manager::Yarn ; "yarn")]
+    #[test_case(Manager::Pnpm ; "pnpm")]
+    #[test_case(Manager::Rush ; "rush")]
+    #[test_case(Manager::Npm ; "npm")]
+    #[test_case(Manager::Lerna ; "lerna")]
+    fn all_files_include_primary_file_and_lockfile(given: Manager) {
+        let files: Vec<_> = given.all_files().into_iter().map(Path::new).collect();
+        assert!(files.contains(&given.primary_file()));
+        if let Some(lockfile) = given.lockfile() {
+            assert!(files.contains(&lockfile));
+        }
+    }
+
+    #[test_case(
+        Manager::Npm,
+        Manager::Pnpm,
+        &["package-lock.json"],
+        &["pnpm-workspace.yaml", "pnpm-lock.yaml"] ;
+        "npm to pnpm"
+    )]
+    #[test_case(Manager::Yarn, Manager::Npm, &["yarn.lock"], &["package-lock.json"] ; "yarn to npm")]
+    #[test_case(Manager::Pnpm, Manager::Pnpm, &[], &[] ; "same manager")]
+    fn migration_plan(
+        from: Manager,
+        to: Manager,
+        remove: &[&'static str],
+        create: &[&'static str],
+    ) {
+        let expected = MigrationPlan {
+            remove: remove.to_vec(),
+            create: create.to_vec(),
+        };
+        assert_eq!(Manager::migration_plan(&from, &to), expected);
+    }
+
+    #[test_case(Manager::Yarn, &["YARN_"] ; "yarn")]
+    #[test_case(Manager::Pnpm, &["PNPM_", "npm_config_"] ; "pnpm")]
+    #[test_case(Manager::Rush, &["RUSH_"] ; "rush")]
+    #[test_case(Manager::Npm, &["npm_", "npm_config_"] ; "npm")]
+    #[test_case(Manager::Lerna, &["LERNA_", "npm_config_"] ; "lerna")]
+    fn env_prefixes(given: Manager, expected: &[&str]) {
+        assert_eq!(given.env_prefixes(), expected);
+    }
+
+    #[test]
+    fn completion_candidates_parse() {
+        let candidates = Manager::completion_candidates();
+        assert_eq!(candidates.len(), SEARCH_ORDER.len());
+        for manager in SEARCH_ORDER {
+            assert!(candidates.contains(&manager.name()));
+        }
+        for candidate in candidates {
+            let manager: Manager = candidate.parse().unwrap();
+            assert_eq!(manager.name(), candidate);
+        }
+    }
+
+    #[test]
+    fn dedup_preserving_precedence() {
+        let managers = [
+            Manager::Npm,
+            Manager::Yarn,
+            Manager::Npm,
+            Manager::Lerna,
+            Manager::Yarn,
+        ];
+        assert_eq!(
+            Manager::dedup_preserving_precedence(&managers),
+            [Manager::Lerna, Manager::Yarn, Manager::Npm]
+        );
+        assert_eq!(Manager::dedup_preserving_precedence(&[]), []);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_available_in() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let bin = tempfile::tempdir().unwrap();
+        let empty = tempfile::tempdir().unwrap();
+        let npm = bin.path().join("npm");
+        fs::write(&npm, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&npm, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(bin.path().join("pnpm"), "not executable").unwrap();
+
+        let path = env::join_paths([empty.path(), bin.path()]).unwrap();
+        assert!(Manager::Npm.is_available_in(&path));
+        assert!(!Manager::Pnpm.is_available_in(&path));
+        assert!(!Manager::Yarn.is_available_in(&path));
+    }
+
     #[test_case(&Path::new("yarn.lock"), Ok(Manager::Yarn) ; "yarn without stem")]
     #[test_case(&Path::new("pnpm-workspace.yaml"), Ok(Manager::Pnpm) ; "pnpm without stem")]
     #[test_case(&Path::new("rush.json"), Ok(Manager::Rush) ; "rush without stem")]
@@ -134,4 +759,37 @@ mod tests {
         let actual = given.try_into();
         assert_eq!(actual, expected);
     }
+
+    #[test_case("Yarn.lock", CaseSensitivity::Sensitive, None ; "sensitive mixed case")]
+    #[test_case("yarn.lock", CaseSensitivity::Sensitive, Some(Manager::Yarn) ; "sensitive exact")]
+    #[test_case("Yarn.lock", CaseSensitivity::Insensitive, Some(Manager::Yarn) ; "insensitive mixed case")]
+    #[test_case("PNPM-Workspace.YAML", CaseSensitivity::Insensitive, Some(Manager::Pnpm) ; "insensitive upper case")]
+    #[test_case("yarn.lock.bak", CaseSensitivity::Insensitive, None ; "insensitive unknown")]
+    #[test_case("Yarn.lock", CaseSensitivity::Auto, cfg!(any(windows, target_os = "macos")).then_some(Manager::Yarn) ; "auto mixed case")]
+    fn from_file_name(given: &str, case_sensitivity: CaseSensitivity, expected: Option<Manager>) {
+        let actual = Manager::from_file_name(Path::new(given), case_sensitivity);
+        assert_eq!(actual.ok(), expected);
+    }
+
+    #[test_case(&Path::new("/foo/yarn.lock"), Ok(Manager::Yarn) ; "valid path")]
+    #[test_case(&Path::new("invalid"), Err(StrictFileError::Invalid(InvalidFileError(PathBuf::from("invalid")))) ; "invalid path")]
+    fn try_from_strict(given: &Path, expected: Result<Manager, StrictFileError>) {
+        assert_eq!(Manager::try_from_strict(given), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn try_from_strict_non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new("/foo").join(OsStr::from_bytes(b"yarn\xff.lock"));
+        assert_eq!(
+            Manager::try_from(path.as_path()),
+            Err(InvalidFileError(path.clone()))
+        );
+        assert_eq!(
+            Manager::try_from_strict(&path),
+            Err(StrictFileError::NonUtf8(path.clone()))
+        );
+    }
 }
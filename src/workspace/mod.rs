@@ -1,5 +1,47 @@
+pub mod builder;
+mod cache;
+mod code_workspace;
+mod diagnostics;
+mod git;
+mod install;
+mod json;
+mod lockfiles;
 pub mod manager;
+mod node;
+mod npmrc;
+mod package_json;
+mod packages;
+mod pnpm;
+#[cfg(feature = "sysinfo")]
+mod process;
+pub mod registry;
+mod resolve;
 pub mod root;
+mod search;
+mod shell;
+mod staleness;
+#[cfg(feature = "tar")]
+mod tarball;
+mod turbo;
+mod walk;
+#[cfg(feature = "watch")]
+mod watch;
+mod worktrees;
+mod yaml;
+mod yarn;
 
-pub use manager::Manager;
-pub use root::Root;
+pub use builder::RootBuilder;
+pub use cache::{RootCache, same_workspace};
+pub use code_workspace::find_roots_from_code_workspace;
+pub use diagnostics::{WorkspaceDiagnostics, WorkspaceTooling};
+pub use lockfiles::LockfileLayout;
+pub use manager::{Manager, manager_files_in};
+pub use packages::Package;
+pub use registry::ManagerRegistry;
+pub use resolve::{Confidence, DetectionSource};
+pub use root::{Result, Root};
+pub use search::{CaseSensitivity, FollowSymlinks, Precedence};
+pub use shell::Shell;
+pub use walk::is_in_node_modules;
+pub use worktrees::find_roots_in_worktrees;
+pub use yarn::YarnMode;
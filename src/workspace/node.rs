@@ -0,0 +1,132 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use super::{
+    package_json,
+    root::{Result, Root},
+};
+
+/// Files naming the Node.js version, in order of precedence.
+const VERSION_FILES: &[&str] = &[".nvmrc", ".node-version"];
+
+impl Root {
+    /// The Node.js version the workspace asks for, taken from the first of:
+    ///
+    /// 1. `.nvmrc` at the root
+    /// 2. `.node-version` at the root
+    /// 3. `engines.node` in the root `package.json`
+    ///
+    /// The version is returned as written, so it may be a range or an alias like `lts/*`.
+    pub fn node_version(&self) -> Result<Option<String>> {
+        for file in VERSION_FILES {
+            let contents = match fs::read_to_string(self.path().join(file)) {
+                Ok(contents) => contents,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error.into()),
+            };
+            // Both files allow `#` comments on their own lines.
+            let version = contents
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with('#'));
+            if let Some(version) = version {
+                return Ok(Some(version.to_string()));
+            }
+        }
+
+        let package = package_json::read(self.path())?;
+        let engine = package
+            .as_ref()
+            .and_then(|package| package.pointer("/engines/node"))
+            .and_then(Value::as_str);
+        Ok(engine.map(String::from))
+    }
+
+    /// The `node_modules` directories Node.js searches when resolving a bare import from
+    /// the directory `from`, nearest first, stopping at the root. Like Node.js, this skips
+    /// directories that are themselves named `node_modules`, and includes directories
+    /// that don't exist.
+    ///
+    /// Empty when `from` is outside of the workspace.
+    pub fn node_modules_dirs(&self, from: &Path) -> Result<Vec<PathBuf>> {
+        let dirs = from
+            .ancestors()
+            .take_while(|dir| dir.starts_with(self.path()))
+            .filter(|dir| dir.file_name().is_none_or(|name| name != "node_modules"))
+            .map(|dir| dir.join("node_modules"))
+            .collect();
+        Ok(dirs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    const ENGINES: &str = r#"{ "engines": { "node": ">=18" } }"#;
+
+    #[test_case(&[(".nvmrc", "v20.11.0\n"), ("package.json", ENGINES)], Some("v20.11.0") ; "nvmrc")]
+    #[test_case(&[(".node-version", "# pinned\n18.19.0\n")], Some("18.19.0") ; "node version")]
+    #[test_case(&[(".nvmrc", "lts/iron"), (".node-version", "18.19.0")], Some("lts/iron") ; "nvmrc first")]
+    #[test_case(&[("package.json", ENGINES)], Some(">=18") ; "engines only")]
+    #[test_case(&[("package.json", "{}")], None ; "unspecified")]
+    fn node_version(files: &[(&str, &str)], expected: Option<&str>) {
+        let dir = fixture(&["yarn.lock"]);
+        for (file, contents) in files {
+            write(dir.path(), file, contents);
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.node_version().unwrap().as_deref(), expected);
+    }
+
+    #[test]
+    fn node_modules_dirs_from_nested_package() {
+        let dir = fixture(&["yarn.lock", "packages/app/src/index.js"]);
+        let root = Root::new(dir.path()).unwrap();
+
+        let from = dir.path().join("packages/app/src");
+        let expected: Vec<_> = [
+            "packages/app/src/node_modules",
+            "packages/app/node_modules",
+            "packages/node_modules",
+            "node_modules",
+        ]
+        .iter()
+        .map(|node_modules| dir.path().join(node_modules))
+        .collect();
+        assert_eq!(root.node_modules_dirs(&from).unwrap(), expected);
+    }
+
+    #[test]
+    fn node_modules_dirs_skips_node_modules() {
+        let dir = fixture(&["yarn.lock", "node_modules/foo/index.js"]);
+        let root = Root::new(dir.path()).unwrap();
+
+        let from = dir.path().join("node_modules/foo");
+        let expected = [
+            dir.path().join("node_modules/foo/node_modules"),
+            dir.path().join("node_modules"),
+        ];
+        assert_eq!(root.node_modules_dirs(&from).unwrap(), expected);
+    }
+
+    #[test]
+    fn node_modules_dirs_outside_root() {
+        let dir = fixture(&["web/yarn.lock", "api/index.js"]);
+        let root = Root::new(dir.path().join("web")).unwrap();
+        assert!(
+            root.node_modules_dirs(&dir.path().join("api"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::{collections::HashMap, fs, io};
+
+use super::root::{Result, Root};
+
+impl Root {
+    /// The settings in the root `.npmrc`, such as `registry`, scoped registries like
+    /// `@acme:registry`, and per-registry auth like `//registry.npmjs.org/:_authToken`.
+    /// Empty if there's no `.npmrc`.
+    ///
+    /// Lines starting with `#` or `;` are comments, a key without a value is `true`, and
+    /// a later line overrides an earlier one with the same key, as in npm. Values are kept
+    /// verbatim rather than interpolated, so `${NPM_TOKEN}` stays as written.
+    pub fn npmrc(&self) -> Result<HashMap<String, String>> {
+        match fs::read_to_string(self.path().join(".npmrc")) {
+            Ok(npmrc) => Ok(parse(&npmrc)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+fn parse(npmrc: &str) -> HashMap<String, String> {
+    npmrc
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+        .map(|line| match line.split_once('=') {
+            Some((key, value)) => (key.trim().to_string(), unquote(value.trim()).to_string()),
+            None => (line.to_string(), String::from("true")),
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test]
+    fn npmrc() {
+        let dir = fixture(&["package-lock.json"]);
+        write(
+            dir.path(),
+            ".npmrc",
+            "# Mirror the public registry.
+registry=https://registry.npmjs.org/
+registry = https://mirror.example.com/npm/
+@acme:registry=https://npm.pkg.github.com
+//npm.pkg.github.com/:_authToken=${GITHUB_TOKEN}
+; legacy settings
+save-exact
+engine-strict = \"true\"
+",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        let expected = HashMap::from([
+            (
+                String::from("registry"),
+                String::from("https://mirror.example.com/npm/"),
+            ),
+            (
+                String::from("@acme:registry"),
+                String::from("https://npm.pkg.github.com"),
+            ),
+            (
+                String::from("//npm.pkg.github.com/:_authToken"),
+                String::from("${GITHUB_TOKEN}"),
+            ),
+            (String::from("save-exact"), String::from("true")),
+            (String::from("engine-strict"), String::from("true")),
+        ]);
+        assert_eq!(root.npmrc().unwrap(), expected);
+    }
+
+    #[test]
+    fn npmrc_absent() {
+        let dir = fixture(&["package-lock.json"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.npmrc().unwrap(), HashMap::new());
+    }
+}
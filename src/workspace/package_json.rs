@@ -0,0 +1,242 @@
+use std::{collections::HashMap, path::Path};
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use super::{
+    json,
+    manager::Manager,
+    root::{Result, Root},
+};
+
+/// Reads and parses the `package.json` in `dir`, or `None` if there isn't one.
+pub(crate) fn read(dir: &Path) -> Result<Option<Value>> {
+    json::read(&dir.join("package.json"))
+}
+
+/// Flattens npm's nested overrides into pnpm's `parent>child` keys, where a `.` key
+/// overrides the parent itself.
+fn flatten_overrides(prefix: Option<&str>, overrides: &Value, flat: &mut HashMap<String, String>) {
+    let Some(overrides) = overrides.as_object() else {
+        return;
+    };
+
+    for (name, value) in overrides {
+        let key = match (prefix, name.as_str()) {
+            (Some(prefix), ".") => prefix.to_string(),
+            (Some(prefix), name) => format!("{prefix}>{name}"),
+            (None, name) => name.to_string(),
+        };
+        match value {
+            Value::String(version) => {
+                flat.insert(key, version.clone());
+            }
+            nested => flatten_overrides(Some(&key), nested, flat),
+        }
+    }
+}
+
+/// The string entries of the `scripts` object of a parsed `package.json`, in the order
+/// they're declared.
+pub(crate) fn scripts(package: &Value) -> IndexMap<String, String> {
+    package
+        .get("scripts")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, command)| Some((name.clone(), command.as_str()?.to_string())))
+        .collect()
+}
+
+impl Root {
+    /// Whether the root `package.json` pins its manager through a well-formed
+    /// `packageManager` field, as used by corepack.
+    pub fn is_corepack_managed(&self) -> Result<bool> {
+        let Some(package) = read(self.path())? else {
+            return Ok(false);
+        };
+
+        let spec = package.get("packageManager").and_then(Value::as_str);
+        Ok(spec.is_some_and(|spec| {
+            matches!(Manager::from_corepack_spec(spec), Ok((_, Some(_version))))
+        }))
+    }
+
+    /// Whether the root `package.json` has `"private": true`, which keeps it from being
+    /// published. `false` when the field or the file is absent.
+    pub fn is_private(&self) -> Result<bool> {
+        let package = read(self.path())?;
+        let private = package
+            .as_ref()
+            .and_then(|package| package.get("private"))
+            .and_then(Value::as_bool);
+        Ok(private.unwrap_or(false))
+    }
+
+    /// The dependency version pins of the root `package.json`, keyed by package.
+    ///
+    /// The field depends on the manager:
+    ///
+    /// - yarn: `resolutions`
+    /// - npm and lerna: `overrides`, with nested overrides flattened to `parent>child`
+    /// - pnpm: `pnpm.overrides`
+    /// - rush: none, since rush keeps its pins outside of `package.json`
+    pub fn overrides(&self) -> Result<HashMap<String, String>> {
+        let pointer = match self.manager() {
+            Manager::Yarn => "/resolutions",
+            Manager::Npm | Manager::Lerna => "/overrides",
+            Manager::Pnpm => "/pnpm/overrides",
+            Manager::Rush => return Ok(HashMap::new()),
+        };
+
+        let mut overrides = HashMap::new();
+        if let Some(package) = read(self.path())?
+            && let Some(field) = package.pointer(pointer)
+        {
+            flatten_overrides(None, field, &mut overrides);
+        }
+        Ok(overrides)
+    }
+
+    /// The `scripts` of the root `package.json` by name, iterating in the order they're
+    /// declared. Empty when there are none.
+    pub fn scripts(&self) -> Result<IndexMap<String, String>> {
+        let package = read(self.path())?;
+        Ok(package.as_ref().map(scripts).unwrap_or_default())
+    }
+
+    /// The yarn classic `workspaces.nohoist` patterns of the root `package.json`.
+    ///
+    /// Empty when there are none, including when `workspaces` is the plain array shorthand.
+    pub fn nohoist_patterns(&self) -> Result<Vec<String>> {
+        let Some(package) = read(self.path())? else {
+            return Ok(Vec::new());
+        };
+
+        let patterns = package
+            .pointer("/workspaces/nohoist")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect();
+        Ok(patterns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test_case(r#"{ "packageManager": "pnpm@8.6.0" }"#, true ; "with version")]
+    #[test_case(r#"{ "packageManager": "yarn@4.1.0+sha512.abc" }"#, true ; "with hash")]
+    #[test_case(r#"{ "packageManager": "pnpm" }"#, false ; "without version")]
+    #[test_case(r#"{ "packageManager": "pnpm@^8" }"#, false ; "with range")]
+    #[test_case(r#"{ "packageManager": "lolwut@1.0.0" }"#, false ; "unknown manager")]
+    #[test_case(r#"{ "name": "foo" }"#, false ; "without field")]
+    fn is_corepack_managed(package_json: &str, expected: bool) {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        write(dir.path(), "package.json", package_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.is_corepack_managed().unwrap(), expected);
+    }
+
+    #[test]
+    fn is_corepack_managed_without_package_json() {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.is_corepack_managed().unwrap());
+    }
+
+    #[test_case(
+        r#"{ "workspaces": { "packages": ["packages/*"], "nohoist": ["**/react-native", "**/react-native/**"] } }"#,
+        &["**/react-native", "**/react-native/**"] ;
+        "object form"
+    )]
+    #[test_case(r#"{ "workspaces": { "packages": ["packages/*"] } }"#, &[] ; "object form without nohoist")]
+    #[test_case(r#"{ "workspaces": ["packages/*"] }"#, &[] ; "array shorthand")]
+    #[test_case(r#"{ "name": "foo" }"#, &[] ; "without workspaces")]
+    fn nohoist_patterns(package_json: &str, expected: &[&str]) {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), "package.json", package_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.nohoist_patterns().unwrap(), expected);
+    }
+
+    #[test_case(
+        "yarn.lock",
+        r#"{ "resolutions": { "lodash": "4.17.21", "webpack/**/terser": "5.0.0" } }"#,
+        &[("lodash", "4.17.21"), ("webpack/**/terser", "5.0.0")] ;
+        "yarn resolutions"
+    )]
+    #[test_case(
+        "package-lock.json",
+        r#"{ "overrides": { "lodash": "4.17.21", "react": { ".": "18.2.0", "loose-envify": "1.4.0" } } }"#,
+        &[("lodash", "4.17.21"), ("react", "18.2.0"), ("react>loose-envify", "1.4.0")] ;
+        "npm overrides"
+    )]
+    #[test_case(
+        "pnpm-workspace.yaml",
+        r#"{ "pnpm": { "overrides": { "lodash": "4.17.21", "foo>bar": "^1.0.0" } } }"#,
+        &[("lodash", "4.17.21"), ("foo>bar", "^1.0.0")] ;
+        "pnpm overrides"
+    )]
+    #[test_case(
+        "pnpm-workspace.yaml",
+        r#"{ "overrides": { "lodash": "4.17.21" } }"#,
+        &[] ;
+        "other manager's field"
+    )]
+    fn overrides(manager_file: &str, package_json: &str, expected: &[(&str, &str)]) {
+        let dir = fixture(&[manager_file]);
+        write(dir.path(), "package.json", package_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        let expected: HashMap<_, _> = expected
+            .iter()
+            .map(|&(name, version)| (name.to_string(), version.to_string()))
+            .collect();
+        assert_eq!(root.overrides().unwrap(), expected);
+    }
+
+    #[test_case(Some(r#"{ "private": true }"#), true ; "private")]
+    #[test_case(Some(r#"{ "private": false }"#), false ; "public")]
+    #[test_case(Some(r#"{ "name": "foo" }"#), false ; "absent")]
+    #[test_case(None, false ; "without package json")]
+    fn is_private(package_json: Option<&str>, expected: bool) {
+        let dir = fixture(&["yarn.lock"]);
+        if let Some(package_json) = package_json {
+            write(dir.path(), "package.json", package_json);
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.is_private().unwrap(), expected);
+    }
+
+    #[test_case(
+        r#"{ "scripts": { "build": "tsc -b", "test": "vitest", "lint": "eslint ." } }"#,
+        &[("build", "tsc -b"), ("test", "vitest"), ("lint", "eslint .")] ;
+        "in declaration order"
+    )]
+    #[test_case(r#"{ "scripts": { "build": "tsc", "broken": 1 } }"#, &[("build", "tsc")] ; "skips non-strings")]
+    #[test_case(r#"{ "name": "foo" }"#, &[] ; "without scripts")]
+    fn scripts(package_json: &str, expected: &[(&str, &str)]) {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), "package.json", package_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        let scripts = root.scripts().unwrap();
+        // Compared as pairs, as maps are equal regardless of order.
+        let actual: Vec<_> = scripts
+            .iter()
+            .map(|(name, command)| (name.as_str(), command.as_str()))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+}
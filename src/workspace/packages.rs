@@ -0,0 +1,533 @@
+use std::{
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern};
+use indexmap::IndexMap;
+
+use super::{
+    json,
+    manager::Manager,
+    package_json, pnpm,
+    root::{Result, Root, RootError},
+    walk::walk_files_until,
+};
+
+/// Lerna's `packages` when `lerna.json` doesn't list any.
+const LERNA_DEFAULT_PACKAGES: &str = "packages/*";
+
+/// `*` must not cross directories, so `packages/*` doesn't match `packages/a/b`.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A workspace package found by [`Root::packages_detailed`], with the fields of its
+/// `package.json` that callers most often need.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The directory containing the package's `package.json`.
+    pub path: PathBuf,
+    /// The `name` field, if any.
+    pub name: Option<String>,
+    /// The `version` field, if any.
+    pub version: Option<String>,
+    /// Whether `"private": true` is set.
+    pub private: bool,
+    /// The `scripts` by name, iterating in the order they're declared.
+    pub scripts: IndexMap<String, String>,
+}
+
+impl Package {
+    /// Reads the `package.json` in `path`.
+    fn read(path: PathBuf) -> Result<Self> {
+        let package = package_json::read(&path)?.unwrap_or_default();
+        let string = |field| package.get(field).and_then(serde_json::Value::as_str);
+        Ok(Self {
+            name: string("name").map(String::from),
+            version: string("version").map(String::from),
+            private: package
+                .get("private")
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false),
+            scripts: package_json::scripts(&package),
+            path,
+        })
+    }
+}
+
+/// Collects the string entries of a JSON array.
+fn json_strings(value: Option<&serde_json::Value>) -> Vec<String> {
+    value
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_str)
+        .map(String::from)
+        .collect()
+}
+
+/// The `workspaces` globs of a `package.json`, in either the array or object form.
+fn package_json_globs(dir: &Path) -> Result<Vec<String>> {
+    let Some(package) = package_json::read(dir)? else {
+        return Ok(Vec::new());
+    };
+
+    let workspaces = package.get("workspaces");
+    Ok(
+        match workspaces.and_then(|workspaces| workspaces.get("packages")) {
+            Some(packages) => json_strings(Some(packages)),
+            None => json_strings(workspaces),
+        },
+    )
+}
+
+impl Root {
+    /// The package globs of the workspace, from the manager's config.
+    ///
+    /// - npm and yarn: `workspaces` in `package.json`
+    /// - pnpm: `packages` in `pnpm-workspace.yaml`
+    /// - lerna: `packages` in `lerna.json`, then `workspaces`, then `packages/*`
+    /// - rush: the `projectFolder` of each of `projects` in `rush.json`
+    ///
+    /// Whatever the manager, the globs are in one flavor: relative to the root, `/`
+    /// separated, without a leading `./` or trailing `/`, and starting with `!` to exclude
+    /// packages matched by the others. `*` matches within a directory and `**` across
+    /// them. Rush's folders are literal paths, so any glob syntax in them is escaped.
+    pub fn workspace_globs(&self) -> Result<Vec<String>> {
+        let globs = self.raw_workspace_globs()?;
+        Ok(globs.iter().filter_map(|glob| normalize(glob)).collect())
+    }
+
+    /// The package globs as written in the manager's config.
+    fn raw_workspace_globs(&self) -> Result<Vec<String>> {
+        match self.manager() {
+            Manager::Npm | Manager::Yarn => package_json_globs(self.path()),
+            Manager::Pnpm => {
+                let workspace = pnpm::read_workspace_yaml(self.path())?;
+                let packages = workspace
+                    .as_ref()
+                    .and_then(|workspace| workspace.get("packages"))
+                    .and_then(serde_yaml::Value::as_sequence);
+                Ok(packages
+                    .into_iter()
+                    .flatten()
+                    .filter_map(serde_yaml::Value::as_str)
+                    .map(String::from)
+                    .collect())
+            }
+            Manager::Lerna => {
+                let lerna = json::read(&self.manager_file())?;
+                let globs = json_strings(lerna.as_ref().and_then(|lerna| lerna.get("packages")));
+                if !globs.is_empty() {
+                    return Ok(globs);
+                }
+                let globs = package_json_globs(self.path())?;
+                if !globs.is_empty() {
+                    return Ok(globs);
+                }
+                Ok(vec![String::from(LERNA_DEFAULT_PACKAGES)])
+            }
+            Manager::Rush => {
+                let rush = json::read(&self.manager_file())?;
+                let projects = rush
+                    .as_ref()
+                    .and_then(|rush| rush.get("projects"))
+                    .and_then(serde_json::Value::as_array);
+                Ok(projects
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|project| project.get("projectFolder")?.as_str())
+                    .map(Pattern::escape)
+                    .collect())
+            }
+        }
+    }
+
+    /// The directories of the workspace's packages, i.e. those with a `package.json`
+    /// matched by [`Root::workspace_globs`]. Packages inside `node_modules` are skipped.
+    ///
+    /// The directories are sorted by path, so the result doesn't depend on the order in
+    /// which the filesystem lists them.
+    pub fn packages(&self) -> Result<Vec<PathBuf>> {
+        let mut packages = Vec::new();
+        self.for_each_package(|dir| {
+            packages.push(dir.to_path_buf());
+            ControlFlow::Continue(())
+        })?;
+        packages.sort();
+        Ok(packages)
+    }
+
+    /// Like [`Root::packages`], but also reads each package's `package.json` into a
+    /// [`Package`].
+    pub fn packages_detailed(&self) -> Result<Vec<Package>> {
+        self.packages()?.into_iter().map(Package::read).collect()
+    }
+
+    /// Calls `visit` with each package directory of [`Root::packages`] as it's found,
+    /// without collecting them, until `visit` breaks. Packages are visited in the order
+    /// the filesystem lists them.
+    pub fn for_each_package(&self, mut visit: impl FnMut(&Path) -> ControlFlow<()>) -> Result<()> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for glob in self.workspace_globs()? {
+            match glob.strip_prefix('!') {
+                Some(glob) => exclude.extend(patterns(glob)?),
+                None => include.extend(patterns(&glob)?),
+            }
+        }
+
+        let matches = |patterns: &[Pattern], dir: &Path| {
+            patterns
+                .iter()
+                .any(|pattern| pattern.matches_path_with(dir, MATCH_OPTIONS))
+        };
+
+        walk_files_until(self.path(), &mut |path| {
+            if path.file_name().is_none_or(|name| name != "package.json") {
+                return ControlFlow::Continue(());
+            }
+            let Some(dir) = path.parent() else {
+                return ControlFlow::Continue(());
+            };
+            let Ok(relative) = dir.strip_prefix(self.path()) else {
+                return ControlFlow::Continue(());
+            };
+            if !relative.as_os_str().is_empty()
+                && matches(&include, relative)
+                && !matches(&exclude, relative)
+            {
+                return visit(dir);
+            }
+            ControlFlow::Continue(())
+        })?;
+        Ok(())
+    }
+
+    /// The directory of the package whose `package.json` is named `name`, e.g. to back
+    /// `yarn workspace <name>` or `pnpm --filter <name>`.
+    pub fn resolve_package_by_name(&self, name: &str) -> Result<Option<PathBuf>> {
+        for dir in self.packages()? {
+            let package = package_json::read(&dir)?;
+            let package_name = package
+                .as_ref()
+                .and_then(|package| package.get("name"))
+                .and_then(serde_json::Value::as_str);
+            if package_name == Some(name) {
+                return Ok(Some(dir));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Rewrites a glob as written by a manager in the flavor of [`Root::workspace_globs`],
+/// or `None` if nothing is left of it.
+fn normalize(glob: &str) -> Option<String> {
+    let glob = glob.trim();
+    let (negation, glob) = match glob.strip_prefix('!') {
+        Some(glob) => ("!", glob),
+        None => ("", glob),
+    };
+
+    let components: Vec<_> = glob
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect();
+    if components.is_empty() {
+        return None;
+    }
+    Some(format!("{negation}{}", components.join("/")))
+}
+
+/// Compiles a normalized workspace glob.
+///
+/// Like the managers' own matchers, a trailing `/**` also matches the directory itself,
+/// so `!**/test/**` excludes `packages/test` as well as everything below it.
+fn patterns(glob: &str) -> Result<Vec<Pattern>> {
+    let mut patterns = vec![pattern(glob)?];
+    if let Some(dir) = glob.strip_suffix("/**") {
+        patterns.push(pattern(dir)?);
+    }
+    Ok(patterns)
+}
+
+fn pattern(glob: &str) -> Result<Pattern> {
+    Pattern::new(glob).map_err(|error| RootError::Manager(format!("Invalid glob {glob}: {error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test_case(r#"{ "workspaces": ["packages/*", "tools/cli"] }"# ; "array form")]
+    #[test_case(r#"{ "workspaces": { "packages": ["packages/*", "tools/cli"] } }"# ; "object form")]
+    fn workspace_globs_from_package_json(package_json: &str) {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), "package.json", package_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.workspace_globs().unwrap(), ["packages/*", "tools/cli"]);
+    }
+
+    #[test_case("bun.lockb" ; "binary lockfile")]
+    #[test_case("bun.lock" ; "text lockfile")]
+    fn workspace_globs_of_bun_repo(lockfile: &str) {
+        let dir = fixture(&[
+            lockfile,
+            "packages/ui/package.json",
+            "apps/web/package.json",
+        ]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["packages/*", "apps/*"] }"#,
+        );
+
+        // There's no bun manager, so a bun lockfile alone marks no root, and a bun repo is
+        // only found leniently, as npm, from its `package.json`.
+        assert!(Root::new(dir.path()).unwrap_err().is_not_found());
+        let root = Root::new_lenient(dir.path()).unwrap();
+        assert_eq!(root.manager(), Manager::Npm);
+        assert_eq!(root.workspace_globs().unwrap(), ["packages/*", "apps/*"]);
+        assert_eq!(
+            root.packages().unwrap(),
+            [dir.path().join("apps/web"), dir.path().join("packages/ui")]
+        );
+    }
+
+    #[test]
+    fn workspace_globs_from_pnpm_workspace_yaml() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'packages/*'\n  - '!packages/private'\n",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.workspace_globs().unwrap(),
+            ["packages/*", "!packages/private"]
+        );
+    }
+
+    #[test_case(
+        "shared: &shared\n  - packages/*\n  - apps/*\npackages: *shared\n" ;
+        "aliased sequence"
+    )]
+    #[test_case(
+        "x-apps: &apps apps/*\npackages:\n  - packages/*\n  - *apps\n" ;
+        "aliased entry"
+    )]
+    fn workspace_globs_resolve_pnpm_yaml_aliases(workspace_yaml: &str) {
+        let dir = fixture(&[]);
+        write(dir.path(), "pnpm-workspace.yaml", workspace_yaml);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.workspace_globs().unwrap(), ["packages/*", "apps/*"]);
+    }
+
+    #[test_case("packages/*", Some("packages/*") ; "already normalized")]
+    #[test_case("./packages/*/", Some("packages/*") ; "leading dot and trailing slash")]
+    #[test_case("!./packages//private/", Some("!packages/private") ; "negation")]
+    #[test_case("  packages/**  ", Some("packages/**") ; "surrounding whitespace")]
+    #[test_case("./", None ; "nothing left")]
+    fn normalize(glob: &str, expected: Option<&str>) {
+        assert_eq!(super::normalize(glob).as_deref(), expected);
+    }
+
+    #[test]
+    fn workspace_globs_normalized_across_managers() {
+        let dir = fixture(&["yarn.lock"]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["./packages/*/", "!./packages/private"] }"#,
+        );
+        let yarn = Root::new(dir.path()).unwrap();
+
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - packages/*\n  - '!packages/private/'\n",
+        );
+        let pnpm = Root::new(dir.path()).unwrap();
+
+        let expected = ["packages/*", "!packages/private"];
+        assert_eq!(yarn.workspace_globs().unwrap(), expected);
+        assert_eq!(pnpm.workspace_globs().unwrap(), expected);
+    }
+
+    #[test]
+    fn workspace_globs_escape_rush_folders() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "rush.json",
+            r#"{ "projects": [{ "packageName": "app", "projectFolder": "apps/[legacy]app/" }] }"#,
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.workspace_globs().unwrap(), ["apps/[[]legacy[]]app"]);
+    }
+
+    #[test]
+    fn workspace_globs_from_rush_json() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "rush.json",
+            r#"{ "projects": [{ "packageName": "app", "projectFolder": "apps/app" }] }"#,
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.workspace_globs().unwrap(), ["apps/app"]);
+    }
+
+    #[test]
+    fn workspace_globs_lerna_default() {
+        let dir = fixture(&["lerna.json"]);
+        write(dir.path(), "lerna.json", "{}");
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.workspace_globs().unwrap(), [LERNA_DEFAULT_PACKAGES]);
+    }
+
+    #[test]
+    fn packages_with_negation() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/a/package.json",
+            "packages/b/package.json",
+            "packages/private/package.json",
+            "packages/no-manifest/index.js",
+            "packages/a/node_modules/dep/package.json",
+        ]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["packages/*", "!packages/private"] }"#,
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.packages().unwrap(),
+            [dir.path().join("packages/a"), dir.path().join("packages/b")]
+        );
+    }
+
+    #[test]
+    fn packages_pnpm_recursive_glob_with_quoted_negation() {
+        let dir = fixture(&[
+            "packages/a/package.json",
+            "packages/group/b/package.json",
+            "packages/group/b/test/fixture/package.json",
+            "packages/test/package.json",
+            "tools/c/package.json",
+        ]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - packages/**\n  - '!**/test/**'\n",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.packages().unwrap(),
+            [
+                dir.path().join("packages/a"),
+                dir.path().join("packages/group/b"),
+            ]
+        );
+    }
+
+    #[test_case("packages:\n  - \"packages/*\"\n  - \"!packages/private\"\n" ; "double quoted")]
+    #[test_case("packages: ['packages/*', '!packages/private']\n" ; "flow sequence")]
+    fn packages_pnpm_negation_quoting(workspace_yaml: &str) {
+        let dir = fixture(&["packages/a/package.json", "packages/private/package.json"]);
+        write(dir.path(), "pnpm-workspace.yaml", workspace_yaml);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.packages().unwrap(), [dir.path().join("packages/a")]);
+    }
+
+    #[test]
+    fn packages_sorted() {
+        let dir = fixture(&["yarn.lock"]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["tools/*", "apps/*", "packages/*"] }"#,
+        );
+        for package in ["tools/z", "packages/m", "apps/b", "packages/c", "apps/a"] {
+            write(&dir.path().join(package), "package.json", "{}");
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        let expected: Vec<_> = ["apps/a", "apps/b", "packages/c", "packages/m", "tools/z"]
+            .iter()
+            .map(|package| dir.path().join(package))
+            .collect();
+        assert_eq!(root.packages().unwrap(), expected);
+    }
+
+    #[test]
+    fn for_each_package_stops_on_break() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/a/package.json",
+            "packages/b/package.json",
+            "packages/c/package.json",
+        ]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["packages/*"] }"#,
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        let mut visited = Vec::new();
+        root.for_each_package(|dir| {
+            visited.push(dir.to_path_buf());
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert_eq!(visited.len(), 1);
+        assert!(root.packages().unwrap().contains(&visited[0]));
+    }
+
+    #[test_case("foo", Some("packages/foo") ; "exact match")]
+    #[test_case("@scope/bar", Some("packages/bar") ; "scoped name")]
+    #[test_case("missing", None ; "nonexistent name")]
+    fn resolve_package_by_name(name: &str, expected: Option<&str>) {
+        let dir = fixture(&["pnpm-lock.yaml"]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'packages/*'\n",
+        );
+        write(
+            dir.path(),
+            "packages/foo/package.json",
+            r#"{ "name": "foo" }"#,
+        );
+        write(
+            dir.path(),
+            "packages/bar/package.json",
+            r#"{ "name": "@scope/bar" }"#,
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        let expected = expected.map(|package| dir.path().join(package));
+        assert_eq!(root.resolve_package_by_name(name).unwrap(), expected);
+    }
+}
@@ -0,0 +1,205 @@
+use std::{collections::HashMap, path::Path};
+
+use serde_yaml::Value;
+
+use super::{
+    manager::Manager,
+    package_json,
+    root::{Result, Root},
+    yaml,
+};
+
+/// Reads and parses the `pnpm-workspace.yaml` in `dir`, or `None` if there isn't one.
+pub(crate) fn read_workspace_yaml(dir: &Path) -> Result<Option<Value>> {
+    yaml::read(&dir.join(Manager::Pnpm.primary_file()))
+}
+
+impl Root {
+    /// The versions pinned by the `catalog:` and `catalogs:` sections of `pnpm-workspace.yaml`.
+    ///
+    /// Entries from the default catalog are keyed by package name, while entries from
+    /// a named catalog are keyed by `<catalog>:<package>`, e.g. `react17:react`.
+    pub fn pnpm_catalog(&self) -> Result<HashMap<String, String>> {
+        let Some(workspace) = read_workspace_yaml(self.path())? else {
+            return Ok(HashMap::new());
+        };
+
+        let mut catalog = entries(&workspace["catalog"])
+            .map(|(package, version)| (package.to_string(), version))
+            .collect::<HashMap<_, _>>();
+
+        for (name, named) in mapping(&workspace["catalogs"]) {
+            let Some(name) = name.as_str() else {
+                continue;
+            };
+            for (package, version) in entries(named) {
+                // The catalog named "default" is the same as the top-level catalog.
+                let key = match name {
+                    "default" => package.to_string(),
+                    _ => format!("{name}:{package}"),
+                };
+                catalog.insert(key, version);
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// The packages allowed to run build scripts like `postinstall`, from
+    /// `onlyBuiltDependencies` in `pnpm-workspace.yaml` or else under the `pnpm` key of the
+    /// root `package.json`. Empty if neither sets it.
+    pub fn pnpm_only_built_dependencies(&self) -> Result<Vec<String>> {
+        let workspace = read_workspace_yaml(self.path())?;
+        if let Some(allowed) = workspace
+            .as_ref()
+            .and_then(|workspace| workspace.get("onlyBuiltDependencies"))
+            .and_then(Value::as_sequence)
+        {
+            return Ok(allowed
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect());
+        }
+
+        let package = package_json::read(self.path())?;
+        let allowed = package
+            .as_ref()
+            .and_then(|package| package.pointer("/pnpm/onlyBuiltDependencies"))
+            .and_then(serde_json::Value::as_array);
+        Ok(allowed
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .map(String::from)
+            .collect())
+    }
+}
+
+fn mapping(value: &Value) -> impl Iterator<Item = (&Value, &Value)> {
+    value.as_mapping().into_iter().flatten()
+}
+
+/// The `package: version` pairs of a catalog, with versions rendered as strings.
+fn entries(value: &Value) -> impl Iterator<Item = (&str, String)> {
+    mapping(value).filter_map(|(package, version)| {
+        let version = match version {
+            Value::String(version) => version.clone(),
+            Value::Number(version) => version.to_string(),
+            _ => return None,
+        };
+        Some((package.as_str()?, version))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        test_utils::{fixture, write},
+        workspace::root::RootError,
+    };
+
+    #[test]
+    fn pnpm_catalog() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:
+  - packages/*
+catalog:
+  react: ^18.2.0
+  lodash: 4.17.21
+catalogs:
+  react17:
+    react: ^17.0.2
+    react-dom: ^17.0.2
+",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        let expected = HashMap::from([
+            (String::from("react"), String::from("^18.2.0")),
+            (String::from("lodash"), String::from("4.17.21")),
+            (String::from("react17:react"), String::from("^17.0.2")),
+            (String::from("react17:react-dom"), String::from("^17.0.2")),
+        ]);
+        assert_eq!(root.pnpm_catalog().unwrap(), expected);
+    }
+
+    #[test]
+    fn pnpm_catalog_absent() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - packages/*\n",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.pnpm_catalog().unwrap(), HashMap::new());
+    }
+
+    #[test]
+    fn pnpm_only_built_dependencies() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:
+  - packages/*
+onlyBuiltDependencies:
+  - esbuild
+  - '@swc/core'
+  - sharp
+",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.pnpm_only_built_dependencies().unwrap(),
+            ["esbuild", "@swc/core", "sharp"]
+        );
+    }
+
+    #[test]
+    fn pnpm_only_built_dependencies_from_package_json() {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "pnpm": { "onlyBuiltDependencies": ["esbuild"] } }"#,
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.pnpm_only_built_dependencies().unwrap(), ["esbuild"]);
+    }
+
+    #[test]
+    fn pnpm_only_built_dependencies_absent() {
+        let dir = fixture(&["pnpm-workspace.yaml", "package.json"]);
+        write(dir.path(), "package.json", "{}");
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.pnpm_only_built_dependencies().unwrap().is_empty());
+    }
+
+    #[test]
+    fn pnpm_only_built_dependencies_malformed() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "onlyBuiltDependencies: [esbuild\n",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(matches!(
+            root.pnpm_only_built_dependencies(),
+            Err(RootError::Yaml(_))
+        ));
+    }
+}
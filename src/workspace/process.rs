@@ -0,0 +1,93 @@
+use std::ffi::OsStr;
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use super::manager::Manager;
+
+impl Manager {
+    /// Guesses the manager that spawned this process from the parent's command line, e.g.
+    /// a `pnpm` parent or `node /usr/lib/node_modules/pnpm/bin/pnpm.cjs`.
+    ///
+    /// This is a last-resort heuristic. Managers usually run scripts through a shell, so the
+    /// parent is often `sh` rather than the manager itself, and it may have exited already.
+    /// The parent is looked up through `sysinfo`, which supports Linux, macOS, Windows, and
+    /// FreeBSD. Elsewhere, or when the parent's command line isn't readable, as for another
+    /// user's process on macOS, this falls back to the parent's name or is `None`.
+    pub fn from_parent_process() -> Option<Manager> {
+        let refresh = ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always);
+        let mut system = System::new();
+
+        let pid = sysinfo::get_current_pid().ok()?;
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, refresh);
+        let parent = system.process(pid)?.parent()?;
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[parent]), false, refresh);
+
+        let parent = system.process(parent)?;
+        from_args(parent.cmd())
+            .or_else(|| Manager::from_process_name(&parent.name().to_string_lossy()))
+    }
+
+    /// The manager whose executable or entry script is `name`, ignoring its directory,
+    /// extension, case, and any `-` suffix, so `C:\bin\Yarn.CMD`, `pnpm.cjs`, `npm-cli.js`,
+    /// and `yarn-4.1.0.cjs` are all recognized.
+    pub fn from_process_name(name: &str) -> Option<Manager> {
+        program(name).parse().ok()
+    }
+}
+
+/// The bare program name of `name`, e.g. `npm` for `/usr/lib/node_modules/npm/bin/npm-cli.js`.
+fn program(name: &str) -> &str {
+    let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    stem.split('-').next().unwrap_or(stem)
+}
+
+/// The manager named by a command line, looking past `node` to the script it runs.
+fn from_args(args: &[impl AsRef<OsStr>]) -> Option<Manager> {
+    let mut args = args
+        .iter()
+        .map(|arg| arg.as_ref().to_string_lossy())
+        .filter(|arg| !arg.is_empty());
+
+    let executable = args.next()?;
+    if program(&executable).eq_ignore_ascii_case("node") {
+        Manager::from_process_name(&args.next()?)
+    } else {
+        Manager::from_process_name(&executable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("yarn", Some(Manager::Yarn) ; "bare name")]
+    #[test_case("/usr/local/bin/pnpm", Some(Manager::Pnpm) ; "absolute path")]
+    #[test_case(r"C:\Program Files\nodejs\Yarn.CMD", Some(Manager::Yarn) ; "windows shim")]
+    #[test_case("npm-cli.js", Some(Manager::Npm) ; "npm entry script")]
+    #[test_case(".yarn/releases/yarn-4.1.0.cjs", Some(Manager::Yarn) ; "yarn release")]
+    #[test_case("lerna", Some(Manager::Lerna) ; "lerna")]
+    #[test_case("bash", None ; "shell")]
+    #[test_case("", None ; "empty")]
+    fn from_process_name(name: &str, expected: Option<Manager>) {
+        assert_eq!(Manager::from_process_name(name), expected);
+    }
+
+    #[test_case(&["pnpm", "install"], Some(Manager::Pnpm) ; "manager")]
+    #[test_case(&["/usr/bin/node", "/usr/lib/node_modules/npm/bin/npm-cli.js", "run", "build"], Some(Manager::Npm) ; "node script")]
+    #[test_case(&["node", "server.js"], None ; "other node script")]
+    #[test_case(&["/bin/sh", "-c", "yarn build"], None ; "shell")]
+    #[test_case(&[], None ; "empty")]
+    fn from_args(args: &[&str], expected: Option<Manager>) {
+        assert_eq!(super::from_args(args), expected);
+    }
+
+    #[test]
+    fn from_parent_process_under_cargo() {
+        // Test binaries are spawned by cargo, which isn't a manager.
+        assert_eq!(Manager::from_parent_process(), None);
+    }
+}
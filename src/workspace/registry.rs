@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use super::manager::{InvalidFileError, Manager, SEARCH_ORDER};
+
+/// Additional manager file names layered over the built-in defaults.
+///
+/// Custom file names take precedence over a manager's built-in file, while the
+/// precedence between managers still follows the usual search order.
+#[derive(Debug, Default)]
+pub struct ManagerRegistry {
+    custom: Vec<(Manager, PathBuf)>,
+}
+
+impl ManagerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an additional file name, such as `yarn.lock.bak`, for `manager`. It may
+    /// also be a path relative to the root, such as `config/yarn.lock.bak`.
+    pub fn register(&mut self, manager: Manager, file_name: impl Into<PathBuf>) -> &mut Self {
+        self.custom.push((manager, file_name.into()));
+        self
+    }
+
+    /// Every candidate file name in search order.
+    pub(crate) fn candidates(&self) -> Vec<&Path> {
+        SEARCH_ORDER
+            .iter()
+            .flat_map(|manager| self.files_for(*manager))
+            .collect()
+    }
+
+    /// The candidate file names for a single manager, custom names first.
+    pub(crate) fn files_for(&self, manager: Manager) -> Vec<&Path> {
+        self.custom
+            .iter()
+            .filter(|(custom, _)| *custom == manager)
+            .map(|(_, file_name)| file_name.as_path())
+            .chain([manager.primary_file()])
+            .collect()
+    }
+
+    /// Maps a found file back to its manager and the root-relative name it matched,
+    /// consulting custom names first. When several custom names match, the longest wins.
+    pub(crate) fn manager_for<'a>(
+        &'a self,
+        path: &Path,
+    ) -> Result<(Manager, &'a Path), InvalidFileError> {
+        let custom = self
+            .custom
+            .iter()
+            .filter(|(_, file_name)| path.ends_with(file_name))
+            .max_by_key(|(_, file_name)| file_name.components().count());
+        match custom {
+            Some((manager, file_name)) => Ok((*manager, file_name)),
+            None => {
+                let manager = Manager::try_from(path)?;
+                Ok((manager, manager.primary_file()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn candidates_layer_custom_names_over_defaults() {
+        let mut registry = ManagerRegistry::new();
+        registry.register(Manager::Yarn, "yarn.lock.bak");
+        assert_eq!(
+            registry.candidates(),
+            [
+                Path::new("lerna.json"),
+                Path::new("rush.json"),
+                Path::new("yarn.lock.bak"),
+                Path::new("yarn.lock"),
+                Path::new("pnpm-workspace.yaml"),
+                Path::new("package-lock.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn manager_for_custom_name() {
+        let mut registry = ManagerRegistry::new();
+        registry.register(Manager::Yarn, "yarn.lock.bak");
+        assert_eq!(
+            registry.manager_for(Path::new("/foo/yarn.lock.bak")),
+            Ok((Manager::Yarn, Path::new("yarn.lock.bak")))
+        );
+        assert_eq!(
+            registry.manager_for(Path::new("/foo/rush.json")),
+            Ok((Manager::Rush, Path::new("rush.json")))
+        );
+    }
+
+    #[test]
+    fn manager_for_nested_custom_path() {
+        let mut registry = ManagerRegistry::new();
+        registry.register(Manager::Pnpm, "yarn.lock.bak");
+        registry.register(Manager::Yarn, "config/yarn.lock.bak");
+        assert_eq!(
+            registry.manager_for(Path::new("/foo/config/yarn.lock.bak")),
+            Ok((Manager::Yarn, Path::new("config/yarn.lock.bak")))
+        );
+        assert_eq!(
+            registry.manager_for(Path::new("/foo/yarn.lock.bak")),
+            Ok((Manager::Pnpm, Path::new("yarn.lock.bak")))
+        );
+    }
+}
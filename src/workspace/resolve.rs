@@ -0,0 +1,431 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::{
+    manager::{Manager, SEARCH_ORDER, VersionPolicy},
+    package_json,
+    root::{Result, Root, RootError},
+    search::SearchOptions,
+};
+use crate::env::{
+    Env, GITHUB_ACTIONS, INPUT_CACHE, JS_WORKSPACE_ROOT, NPM_COMMAND, NPM_CONFIG_USER_AGENT,
+    NPM_EXECPATH, PREFERRED_WORKSPACE_MANAGER, ProcessEnv,
+};
+
+/// The signal that [`Manager::resolve`] used to pick a manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionSource {
+    /// The `PREFERRED_WORKSPACE_MANAGER` environment variable.
+    Env,
+    /// The `packageManager` field of the nearest `package.json` that has one.
+    PackageManager,
+    /// The manager running the current process through `npx` or `pnpm dlx`.
+    Dlx,
+    /// A manager file found by searching up from `cwd`.
+    Lockfile,
+    /// The `npm_config_user_agent` of the manager running the current process.
+    UserAgent,
+    /// The configuration of a CI provider, such as the `cache` input of `setup-node`.
+    CiProvider,
+}
+
+/// How much a [`DetectionSource`] can be trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// The manager was configured explicitly, through the environment or `package.json`.
+    Explicit,
+    /// The manager was inferred from the files present in the workspace.
+    Lockfile,
+    /// The manager was guessed from the process environment, and may be wrong.
+    Heuristic,
+}
+
+impl DetectionSource {
+    pub fn confidence(&self) -> Confidence {
+        match self {
+            DetectionSource::Env | DetectionSource::PackageManager => Confidence::Explicit,
+            DetectionSource::Lockfile => Confidence::Lockfile,
+            DetectionSource::Dlx | DetectionSource::UserAgent | DetectionSource::CiProvider => {
+                Confidence::Heuristic
+            }
+        }
+    }
+}
+
+impl Manager {
+    /// Like [`Manager::resolve`], but reports how reliable the winning signal is.
+    pub fn resolve_with_confidence(cwd: &Path) -> Result<(Manager, Confidence)> {
+        resolve_with_confidence_with(cwd, &ProcessEnv)
+    }
+
+    /// Resolves the manager for `cwd` from every available signal, reporting which one won.
+    ///
+    /// Signals are tried in this order, from most to least explicit:
+    ///
+    /// 1. The `PREFERRED_WORKSPACE_MANAGER` environment variable.
+    /// 2. The `packageManager` field of the nearest `package.json`.
+    /// 3. The manager running the current process through `npx`, `npm exec`, or
+    ///    `pnpm dlx`, as told by `npm_command` and the user agent or `npm_execpath`.
+    /// 4. A manager file found by searching up from `cwd`, as in [`Root::new`].
+    /// 5. The `npm_config_user_agent` set by a manager running the current process.
+    /// 6. The CI provider's configuration, see [`Manager::from_github_actions_env`].
+    ///
+    /// A tool run with `npx` or `pnpm dlx` was started by hand with the user's manager of
+    /// choice, so that beats sniffing files. Scripts run with e.g. `yarn run` set the user
+    /// agent too, but since that's also true of any tool a script spawns, it only counts
+    /// when nothing else is found.
+    ///
+    /// When `JS_WORKSPACE_ROOT` is set, detection starts there instead of at `cwd`. If
+    /// `PREFERRED_WORKSPACE_MANAGER` is also set and disagrees with the manager files in
+    /// that directory, [`RootError::ConflictingOverrides`] is returned.
+    pub fn resolve(cwd: &Path) -> Result<(Manager, DetectionSource)> {
+        resolve_with(cwd, &ProcessEnv)
+    }
+
+    /// The manager named by the `cache` input of `actions/setup-node` when running in
+    /// GitHub Actions, i.e. when `GITHUB_ACTIONS` is `true` and `INPUT_CACHE` is set.
+    pub fn from_github_actions_env() -> Option<Manager> {
+        from_github_actions(&ProcessEnv)
+    }
+
+    /// Parses a user agent like `pnpm/8.6.0 npm/? node/v18.16.0 darwin arm64`.
+    pub fn from_user_agent(user_agent: &str) -> Option<Manager> {
+        let product = user_agent.split_whitespace().next()?;
+        let (name, _version) = product.split_once('/')?;
+        name.parse().ok()
+    }
+}
+
+fn resolve_with_confidence_with(cwd: &Path, env: &impl Env) -> Result<(Manager, Confidence)> {
+    let (manager, source) = resolve_with(cwd, env)?;
+    Ok((manager, source.confidence()))
+}
+
+fn resolve_with(cwd: &Path, env: &impl Env) -> Result<(Manager, DetectionSource)> {
+    let root = env.var(JS_WORKSPACE_ROOT).map(PathBuf::from);
+    let cwd = root.as_deref().unwrap_or(cwd);
+
+    if let Some(preferred) = env.var(PREFERRED_WORKSPACE_MANAGER) {
+        let env_manager = preferred.parse()?;
+        if let Some(detected) = root.as_deref().and_then(manager_at)
+            && detected != env_manager
+        {
+            return Err(RootError::ConflictingOverrides {
+                env_manager,
+                detected,
+            });
+        }
+        return Ok((env_manager, DetectionSource::Env));
+    }
+
+    if let Some(manager) = from_package_manager_field(cwd)? {
+        return Ok((manager, DetectionSource::PackageManager));
+    }
+
+    if let Some(manager) = from_dlx(env) {
+        return Ok((manager, DetectionSource::Dlx));
+    }
+
+    match Root::search(cwd, &SearchOptions::default()) {
+        Ok(root) => Ok((root.manager(), DetectionSource::Lockfile)),
+        Err(error) if error.is_not_found() => env
+            .var(NPM_CONFIG_USER_AGENT)
+            .as_deref()
+            .and_then(Manager::from_user_agent)
+            .map(|manager| (manager, DetectionSource::UserAgent))
+            .or_else(|| {
+                from_github_actions(env).map(|manager| (manager, DetectionSource::CiProvider))
+            })
+            .ok_or(error),
+        Err(error) => Err(error),
+    }
+}
+
+/// The manager running the current process, if it was launched by `npx`, `npm exec`, or
+/// `pnpm dlx`.
+fn from_dlx(env: &impl Env) -> Option<Manager> {
+    let exec_path = env.var(NPM_EXECPATH);
+    let exec_script = exec_path
+        .as_deref()
+        .and_then(|exec_path| Path::new(exec_path).file_stem()?.to_str());
+
+    let dlx = matches!(env.var(NPM_COMMAND).as_deref(), Some("exec" | "dlx"))
+        || matches!(exec_script, Some("npx-cli" | "pnpx"));
+    if !dlx {
+        return None;
+    }
+
+    env.var(NPM_CONFIG_USER_AGENT)
+        .as_deref()
+        .and_then(Manager::from_user_agent)
+        .or_else(|| match exec_script?.split('-').next()? {
+            "npx" => Some(Manager::Npm),
+            "pnpx" => Some(Manager::Pnpm),
+            name => name.parse().ok(),
+        })
+}
+
+fn from_github_actions(env: &impl Env) -> Option<Manager> {
+    if env.var(GITHUB_ACTIONS).as_deref() != Some("true") {
+        return None;
+    }
+
+    // setup-node only supports caching for these managers.
+    match env.var(INPUT_CACHE)?.as_str() {
+        "npm" => Some(Manager::Npm),
+        "yarn" => Some(Manager::Yarn),
+        "pnpm" => Some(Manager::Pnpm),
+        _ => None,
+    }
+}
+
+/// The manager whose manager file or lockfile is directly inside `dir`, if any.
+fn manager_at(dir: &Path) -> Option<Manager> {
+    SEARCH_ORDER.iter().copied().find(|manager| {
+        let lockfile = manager.lockfile().map(|lockfile| dir.join(lockfile));
+        dir.join(manager.primary_file()).exists() || lockfile.is_some_and(|l| l.exists())
+    })
+}
+
+fn from_package_manager_field(cwd: &Path) -> Result<Option<Manager>> {
+    for dir in cwd.ancestors() {
+        let Some(package) = package_json::read(dir)? else {
+            continue;
+        };
+        if let Some(spec) = package.get("packageManager").and_then(Value::as_str) {
+            // Only the name matters here, so don't insist on corepack's exact versions.
+            let (manager, _version) =
+                Manager::from_corepack_spec_with(spec, VersionPolicy::AllowRanges)?;
+            return Ok(Some(manager));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    fn resolve(cwd: &Path, vars: &[(&str, &str)]) -> Result<(Manager, DetectionSource)> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        resolve_with(cwd, &vars)
+    }
+
+    fn resolve_with_confidence(cwd: &Path, vars: &[(&str, &str)]) -> Result<(Manager, Confidence)> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        resolve_with_confidence_with(cwd, &vars)
+    }
+
+    const USER_AGENT: (&str, &str) = (NPM_CONFIG_USER_AGENT, "yarn/1.22.19 npm/? node/v20.9.0");
+
+    #[test]
+    fn env_wins() {
+        let dir = fixture(&["yarn.lock"]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "packageManager": "npm@10.2.0" }"#,
+        );
+
+        let vars = [(PREFERRED_WORKSPACE_MANAGER, "pnpm"), USER_AGENT];
+        let actual = resolve(dir.path(), &vars).unwrap();
+        assert_eq!(actual, (Manager::Pnpm, DetectionSource::Env));
+    }
+
+    #[test]
+    fn package_manager_field_beats_lockfile() {
+        let dir = fixture(&["yarn.lock"]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "packageManager": "npm@10.2.0" }"#,
+        );
+        let cwd = dir.path().join("packages/foo");
+        write(&cwd, "package.json", r#"{ "name": "foo" }"#);
+
+        let actual = resolve(&cwd, &[USER_AGENT]).unwrap();
+        assert_eq!(actual, (Manager::Npm, DetectionSource::PackageManager));
+    }
+
+    #[test]
+    fn lockfile_beats_user_agent() {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        let actual = resolve(dir.path(), &[USER_AGENT]).unwrap();
+        assert_eq!(actual, (Manager::Pnpm, DetectionSource::Lockfile));
+    }
+
+    #[test_case(
+        &[(NPM_COMMAND, "exec"), (NPM_CONFIG_USER_AGENT, "npm/10.2.0 node/v20.9.0 linux x64 workspaces/false")],
+        Manager::Npm ;
+        "npx"
+    )]
+    #[test_case(
+        &[(NPM_COMMAND, "dlx"), (NPM_CONFIG_USER_AGENT, "pnpm/9.1.0 npm/? node/v20.9.0 linux x64")],
+        Manager::Pnpm ;
+        "pnpm dlx"
+    )]
+    #[test_case(
+        &[(NPM_EXECPATH, "/usr/lib/node_modules/npm/bin/npx-cli.js")],
+        Manager::Npm ;
+        "npx without user agent"
+    )]
+    fn dlx_beats_lockfile(vars: &[(&str, &str)], expected: Manager) {
+        let dir = fixture(&["yarn.lock"]);
+        let actual = resolve(dir.path(), vars).unwrap();
+        assert_eq!(actual, (expected, DetectionSource::Dlx));
+    }
+
+    #[test]
+    fn package_manager_field_beats_dlx() {
+        let dir = fixture(&[]);
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "packageManager": "yarn@4.1.0" }"#,
+        );
+
+        let vars = [(NPM_COMMAND, "exec"), USER_AGENT];
+        let actual = resolve(dir.path(), &vars).unwrap();
+        assert_eq!(actual, (Manager::Yarn, DetectionSource::PackageManager));
+    }
+
+    #[test]
+    fn run_script_is_not_dlx() {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        let vars = [(NPM_COMMAND, "run-script"), USER_AGENT];
+        let actual = resolve(dir.path(), &vars).unwrap();
+        assert_eq!(actual, (Manager::Pnpm, DetectionSource::Lockfile));
+    }
+
+    #[test]
+    fn user_agent_as_last_resort() {
+        let dir = fixture(&[]);
+        let actual = resolve(dir.path(), &[USER_AGENT]).unwrap();
+        assert_eq!(actual, (Manager::Yarn, DetectionSource::UserAgent));
+    }
+
+    #[test]
+    fn github_actions_below_user_agent() {
+        let dir = fixture(&[]);
+        let vars = [(GITHUB_ACTIONS, "true"), (INPUT_CACHE, "pnpm")];
+
+        let actual = resolve(dir.path(), &vars).unwrap();
+        assert_eq!(actual, (Manager::Pnpm, DetectionSource::CiProvider));
+
+        let actual = resolve(dir.path(), &[vars[0], vars[1], USER_AGENT]).unwrap();
+        assert_eq!(actual, (Manager::Yarn, DetectionSource::UserAgent));
+    }
+
+    #[test_case(Some("true"), Some("npm"), Some(Manager::Npm) ; "npm")]
+    #[test_case(Some("true"), Some("yarn"), Some(Manager::Yarn) ; "yarn")]
+    #[test_case(Some("true"), Some("pnpm"), Some(Manager::Pnpm) ; "pnpm")]
+    #[test_case(Some("true"), Some("bun"), None ; "unsupported cache")]
+    #[test_case(Some("true"), None, None ; "without cache")]
+    #[test_case(None, Some("pnpm"), None ; "outside of actions")]
+    fn from_github_actions(
+        github_actions: Option<&str>,
+        input_cache: Option<&str>,
+        expected: Option<Manager>,
+    ) {
+        let vars: HashMap<_, _> = [(GITHUB_ACTIONS, github_actions), (INPUT_CACHE, input_cache)]
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect();
+        let actual = super::from_github_actions(&vars);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn root_override_conflicts_with_env() {
+        let dir = fixture(&["pnpm-lock.yaml"]);
+        let root = dir.path().to_str().unwrap();
+        let vars = [
+            (PREFERRED_WORKSPACE_MANAGER, "yarn"),
+            (JS_WORKSPACE_ROOT, root),
+        ];
+
+        let error = resolve(Path::new("/elsewhere"), &vars).unwrap_err();
+        assert!(matches!(
+            error,
+            RootError::ConflictingOverrides {
+                env_manager: Manager::Yarn,
+                detected: Manager::Pnpm,
+            }
+        ));
+    }
+
+    #[test]
+    fn root_override_agrees_with_env() {
+        let dir = fixture(&["pnpm-lock.yaml"]);
+        let root = dir.path().to_str().unwrap();
+        let vars = [
+            (PREFERRED_WORKSPACE_MANAGER, "pnpm"),
+            (JS_WORKSPACE_ROOT, root),
+        ];
+
+        let actual = resolve(Path::new("/elsewhere"), &vars).unwrap();
+        assert_eq!(actual, (Manager::Pnpm, DetectionSource::Env));
+    }
+
+    #[test]
+    fn root_override_replaces_cwd() {
+        let dir = fixture(&["yarn.lock"]);
+        let root = dir.path().to_str().unwrap();
+
+        let actual = resolve(Path::new("/elsewhere"), &[(JS_WORKSPACE_ROOT, root)]).unwrap();
+        assert_eq!(actual, (Manager::Yarn, DetectionSource::Lockfile));
+    }
+
+    #[test_case(DetectionSource::Env, Confidence::Explicit ; "env")]
+    #[test_case(DetectionSource::PackageManager, Confidence::Explicit ; "package manager")]
+    #[test_case(DetectionSource::Dlx, Confidence::Heuristic ; "dlx")]
+    #[test_case(DetectionSource::Lockfile, Confidence::Lockfile ; "lockfile")]
+    #[test_case(DetectionSource::UserAgent, Confidence::Heuristic ; "user agent")]
+    #[test_case(DetectionSource::CiProvider, Confidence::Heuristic ; "ci provider")]
+    fn confidence(given: DetectionSource, expected: Confidence) {
+        assert_eq!(given.confidence(), expected);
+    }
+
+    #[test]
+    fn nothing_found() {
+        let dir = fixture(&[]);
+        let error = resolve(dir.path(), &[]).unwrap_err();
+        assert!(error.is_not_found());
+    }
+
+    #[test_case("pnpm/8.6.0 npm/? node/v18.16.0 darwin arm64", Some(Manager::Pnpm) ; "pnpm")]
+    #[test_case("npm/10.2.0 node/v20.9.0 linux x64 workspaces/false", Some(Manager::Npm) ; "npm")]
+    #[test_case("bun/1.0.0 npm/? node/v21.0.0 linux x64", None ; "unknown")]
+    #[test_case("", None ; "empty")]
+    fn from_user_agent(given: &str, expected: Option<Manager>) {
+        assert_eq!(Manager::from_user_agent(given), expected);
+    }
+
+    #[test_case(&["yarn.lock"], None, &[(PREFERRED_WORKSPACE_MANAGER, "pnpm")], (Manager::Pnpm, Confidence::Explicit) ; "env")]
+    #[test_case(&["yarn.lock"], Some(r#"{ "packageManager": "npm@10.2.0" }"#), &[], (Manager::Npm, Confidence::Explicit) ; "package manager")]
+    #[test_case(&["yarn.lock"], None, &[(NPM_COMMAND, "dlx"), (NPM_CONFIG_USER_AGENT, "pnpm/9.1.0 npm/? node/v20.9.0 linux x64")], (Manager::Pnpm, Confidence::Heuristic) ; "dlx")]
+    #[test_case(&["pnpm-workspace.yaml"], None, &[USER_AGENT], (Manager::Pnpm, Confidence::Lockfile) ; "lockfile")]
+    #[test_case(&[], None, &[USER_AGENT], (Manager::Yarn, Confidence::Heuristic) ; "user agent")]
+    #[test_case(&[], None, &[(GITHUB_ACTIONS, "true"), (INPUT_CACHE, "pnpm")], (Manager::Pnpm, Confidence::Heuristic) ; "ci provider")]
+    fn resolve_with_confidence_per_source(
+        files: &[&str],
+        package_json: Option<&str>,
+        vars: &[(&str, &str)],
+        expected: (Manager, Confidence),
+    ) {
+        let dir = fixture(files);
+        if let Some(package_json) = package_json {
+            write(dir.path(), "package.json", package_json);
+        }
+
+        let actual = resolve_with_confidence(dir.path(), vars).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
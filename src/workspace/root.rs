@@ -1,16 +1,136 @@
 use std::{
-    io,
-    path::{Path, PathBuf},
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+    process::Command,
 };
 
-use super::manager::{self, Manager, SEARCH_ORDER};
+use super::{
+    builder::RootBuilder,
+    git,
+    manager::{self, Manager, SEARCH_ORDER, VersionPolicy},
+    package_json,
+    registry::ManagerRegistry,
+    search::{self, SearchOptions, SearchState, Step, search_up},
+};
+use crate::env::Env;
+
+/// The result of detecting or inspecting a workspace.
+///
+/// ```no_run
+/// use js_workspace::workspace::{Manager, Result, Root};
+///
+/// fn manager() -> Result<Manager> {
+///     Ok(Root::new(".")?.manager())
+/// }
+/// ```
+pub type Result<T> = std::result::Result<T, RootError>;
 
 #[derive(Debug, thiserror::Error)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
 pub enum RootError {
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::io),
+            help("set PREFERRED_WORKSPACE_MANAGER or run from inside a workspace")
+        )
+    )]
     #[error(transparent)]
     Io(#[from] io::Error),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::json),
+            help("check that the workspace's JSON files are well-formed")
+        )
+    )]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::yaml),
+            help("check that the workspace's YAML files are well-formed")
+        )
+    )]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::manager),
+            help("check the value of PREFERRED_WORKSPACE_MANAGER")
+        )
+    )]
     #[error("{0}")]
     Manager(String),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::conflicting_overrides),
+            help(
+                "unset PREFERRED_WORKSPACE_MANAGER or point JS_WORKSPACE_ROOT at a matching workspace"
+            )
+        )
+    )]
+    #[error(
+        "PREFERRED_WORKSPACE_MANAGER is {env_manager:?}, but JS_WORKSPACE_ROOT contains a {detected:?} workspace"
+    )]
+    ConflictingOverrides {
+        env_manager: Manager,
+        detected: Manager,
+    },
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::unexpected_manager),
+            help("migrate the workspace to {expected:?} or update the expected manager")
+        )
+    )]
+    #[error("Expected a {expected:?} workspace, but found a {found:?} workspace")]
+    UnexpectedManager { expected: Manager, found: Manager },
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::bare_repository),
+            help("run from a checkout instead, e.g. one made with `git worktree add`")
+        )
+    )]
+    #[error("{} is a bare git repository without a working tree", .0.display())]
+    BareRepository(PathBuf),
+    #[cfg_attr(feature = "miette", diagnostic(code(js_workspace::all_failed)))]
+    #[error("No workspace found in any candidate directory{}", format_failures(.0))]
+    AllFailed(Vec<(PathBuf, RootError)>),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(js_workspace::too_deep),
+            help("the path has more ancestors than any real directory tree")
+        )
+    )]
+    #[error(
+        "Gave up after searching {} ancestors of {}",
+        search::MAX_ANCESTORS,
+        .0.display()
+    )]
+    TooDeep(PathBuf),
+    #[cfg(feature = "watch")]
+    #[cfg_attr(feature = "miette", diagnostic(code(js_workspace::watch)))]
+    #[error(transparent)]
+    Watch(#[from] notify::Error),
+}
+
+fn format_failures(failures: &[(PathBuf, RootError)]) -> String {
+    failures
+        .iter()
+        .map(|(cwd, error)| format!("\n  {}: {error}", cwd.display()))
+        .collect()
+}
+
+impl RootError {
+    pub(crate) fn is_not_found(&self) -> bool {
+        matches!(self, Self::Io(error) if error.kind() == io::ErrorKind::NotFound)
+    }
 }
 
 impl From<manager::ParseManagerError> for RootError {
@@ -25,49 +145,1090 @@ impl From<manager::InvalidFileError> for RootError {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl From<manager::CorepackSpecError> for RootError {
+    fn from(error: manager::CorepackSpecError) -> Self {
+        Self::Manager(error.to_string())
+    }
+}
+
+impl From<manager::StrictFileError> for RootError {
+    fn from(error: manager::StrictFileError) -> Self {
+        Self::Manager(error.to_string())
+    }
+}
+
+/// Unwraps [`RootError::Io`] as is and wraps every other error as [`io::ErrorKind::Other`].
+impl From<RootError> for io::Error {
+    fn from(error: RootError) -> Self {
+        match error {
+            RootError::Io(error) => error,
+            error => io::Error::other(error),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Root {
     manager: Manager,
     path: PathBuf,
 }
 
+/// Includes the manager file alongside the fields, e.g.
+/// `Root { manager: Yarn, path: "/repo", file: "/repo/yarn.lock" }`.
+impl fmt::Debug for Root {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Root")
+            .field("manager", &self.manager)
+            .field("path", &self.path)
+            .field("file", &self.manager_file())
+            .finish()
+    }
+}
+
 impl Root {
-    pub fn new(cwd: impl AsRef<Path>) -> Result<Self, RootError> {
-        if let Some(manager) = Manager::from_env()? {
-            return Ok(Self::with_manager(cwd, manager)?);
-        }
+    pub fn new(cwd: impl AsRef<Path>) -> Result<Self> {
+        Self::builder().discover(cwd)
+    }
+
+    /// Like [`Root::new`], reading environment overrides like `PREFERRED_WORKSPACE_MANAGER`
+    /// from `env` instead of the process environment.
+    pub fn new_with_env(cwd: impl AsRef<Path>, env: &impl Env) -> Result<Self> {
+        Self::builder().discover_with_env(cwd, env)
+    }
 
-        let mut path = search_up(cwd, SEARCH_ORDER)?;
+    /// Configures how the search for a root is performed.
+    pub fn builder() -> RootBuilder {
+        RootBuilder::new()
+    }
+
+    /// Searches for any manager file in the order of the configured
+    /// [`Precedence`](super::Precedence), ignoring the environment.
+    pub(crate) fn search(cwd: impl AsRef<Path>, options: &SearchOptions) -> Result<Self> {
+        reject_bare_repository(cwd.as_ref())?;
+        let mut path = search_up(cwd, options.precedence.order(), options)?;
         let manager = Manager::try_from(path.as_ref())?;
         path.pop(); // Truncate to the manager file's parent path.
+        Ok(Self::found(manager, path))
+    }
+
+    /// The root for a manager file found in `dir`, unless `dir` is inside the `common/`
+    /// folder of an enclosing Rush workspace.
+    fn found(manager: Manager, dir: PathBuf) -> Self {
+        // Rush keeps package manager files under common/, which must not shadow rush.json.
+        match enclosing_rush_root(&dir) {
+            Some(path) => Self {
+                manager: Manager::Rush,
+                path,
+            },
+            None => Self { manager, path: dir },
+        }
+    }
+
+    /// Like [`Root::new`], but never searches past the root of the enclosing git repository
+    /// or submodule. See [`RootBuilder::stop_at_repo_boundary`].
+    pub fn new_within_repo(cwd: impl AsRef<Path>) -> Result<Self> {
+        Self::builder().stop_at_repo_boundary(true).discover(cwd)
+    }
+
+    /// Like [`Root::new`], but when no workspace is found, falls back to a root at
+    /// `cwd` using the preferred manager from the environment or [`Manager::default`].
+    pub fn new_or_default(cwd: impl AsRef<Path>) -> Result<Self> {
+        let cwd = cwd.as_ref();
+        match Self::new(cwd) {
+            Err(error) if error.is_not_found() => Ok(Self {
+                manager: Manager::from_env()?.unwrap_or_default(),
+                path: cwd.to_path_buf(),
+            }),
+            result => result,
+        }
+    }
+
+    /// Like [`Root::new`], but no workspace being found is `Ok(None)` rather than an error,
+    /// leaving `Err` for when something actually went wrong, like an unreadable file.
+    pub fn try_discover(cwd: impl AsRef<Path>) -> Result<Option<Self>> {
+        match Self::new(cwd) {
+            Ok(root) => Ok(Some(root)),
+            Err(error) if error.is_not_found() => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Like [`Root::new`], but when no manager file is found, the nearest directory with a
+    /// `package.json` is treated as a single-package root. Its manager comes from the
+    /// `packageManager` field, defaulting to npm.
+    pub fn new_lenient(cwd: impl AsRef<Path>) -> Result<Self> {
+        let cwd = cwd.as_ref();
+        match Self::new(cwd) {
+            Err(error) if error.is_not_found() => {
+                let mut path = search_up(cwd, &["package.json"], &SearchOptions::default())?;
+                path.pop();
+
+                let package = package_json::read(&path)?;
+                let spec = package
+                    .as_ref()
+                    .and_then(|package| package.get("packageManager"))
+                    .and_then(serde_json::Value::as_str);
+                let manager = spec
+                    .map(|spec| Manager::from_corepack_spec_with(spec, VersionPolicy::AllowRanges))
+                    .transpose()?
+                    .map_or(Manager::Npm, |(manager, _version)| manager);
+                Ok(Self { manager, path })
+            }
+            result => result,
+        }
+    }
+
+    /// Like [`Root::new`], but starts the search at the parent of `cwd`, e.g. to find the
+    /// workspace enclosing a package that has manager files of its own. A `cwd` without a
+    /// parent, like `/`, is a `NotFound` error.
+    pub fn new_from_parent(cwd: impl AsRef<Path>) -> Result<Self> {
+        let cwd = cwd.as_ref();
+        let parent = cwd.parent().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} has no parent to search from", cwd.display()),
+            )
+        })?;
+        Self::new(parent)
+    }
+
+    /// Like [`Root::new`], but when no manager file is on disk, one that git tracks counts
+    /// too, e.g. a lockfile left out of a sparse checkout or not yet materialized by a
+    /// partial clone.
+    ///
+    /// This runs `git`, which must be on the `PATH`. Without it, or outside a repository,
+    /// this is the same as [`Root::new`].
+    pub fn new_git_aware(cwd: impl AsRef<Path>) -> Result<Self> {
+        let cwd = cwd.as_ref();
+        let error = match Self::new(cwd) {
+            Err(error) if error.is_not_found() => error,
+            result => return result,
+        };
+        let Some(tracked) = git::tracked_files(cwd) else {
+            return Err(error);
+        };
+
+        let mut state = SearchState::new(cwd.to_path_buf(), SEARCH_ORDER, None, &[]);
+        loop {
+            let tracked = match state.poll() {
+                Step::Probe(candidate) | Step::ProbeBoundary(candidate) => {
+                    tracked.contains(candidate)
+                }
+                Step::Found(file) => {
+                    let manager = Manager::try_from(file)?;
+                    let path = file.parent().unwrap_or(file).to_path_buf();
+                    return Ok(Self { manager, path });
+                }
+                Step::NotFound | Step::TooDeep => return Err(error),
+            };
+            state.advance(tracked);
+        }
+    }
+
+    /// Tries [`Root::new`] in each of `cwds` in turn, returning the first success or
+    /// [`RootError::AllFailed`] with every failure if none succeed.
+    pub fn new_first_of(cwds: &[PathBuf]) -> Result<Self> {
+        let mut failures = Vec::new();
+        for cwd in cwds {
+            match Self::new(cwd) {
+                Ok(root) => return Ok(root),
+                Err(error) => failures.push((cwd.clone(), error)),
+            }
+        }
+        Err(RootError::AllFailed(failures))
+    }
+
+    /// Like [`Root::new`], but also returns the path of `cwd` relative to the root.
+    ///
+    /// When `cwd` has `.` or `..` components left after the root, both paths are
+    /// canonicalized to find the offset. If `cwd` then isn't inside the root, e.g. because
+    /// `..` climbed out of a symlink, that's an `InvalidInput` error.
+    pub fn new_with_offset(cwd: impl AsRef<Path>) -> Result<(Self, PathBuf)> {
+        let cwd = cwd.as_ref();
+        let root = Self::new(cwd)?;
+        if let Ok(offset) = cwd.strip_prefix(&root.path)
+            && offset
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Ok((root, offset.to_path_buf()));
+        }
+
+        let offset = cwd
+            .canonicalize()?
+            .strip_prefix(root.canonical_path()?)
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "{} is not inside the workspace root {}",
+                        cwd.display(),
+                        root.path.display()
+                    ),
+                )
+            })?
+            .to_path_buf();
+        Ok((root, offset))
+    }
+
+    /// Like [`Root::new`], but also matches the custom file names in `registry`.
+    pub fn new_with_registry(cwd: impl AsRef<Path>, registry: &ManagerRegistry) -> Result<Self> {
+        let candidates = match Manager::from_env()? {
+            Some(manager) => registry.files_for(manager),
+            None => registry.candidates(),
+        };
+
+        reject_bare_repository(cwd.as_ref())?;
+        let path = search_up(cwd, &candidates, &SearchOptions::default())?;
+        let (manager, file_name) = registry.manager_for(&path)?;
+        let dir = path
+            .ancestors()
+            .nth(file_name.components().count())
+            .ok_or_else(|| manager::InvalidFileError(path.clone()))?
+            .to_path_buf();
+        Ok(Self::found(manager, dir))
+    }
+
+    /// Like [`Root::new`], but only considers the files of the `allowed` managers, with
+    /// their usual precedence. The environment is ignored.
+    pub fn new_restricted(cwd: impl AsRef<Path>, allowed: &[Manager]) -> Result<Self> {
+        let allowed = Manager::dedup_preserving_precedence(allowed);
+        let mut path = search_up(cwd, &allowed, &SearchOptions::default())?;
+        let manager = Manager::try_from(path.as_ref())?;
+        path.pop();
+        Ok(Self { manager, path })
+    }
+
+    /// Builds a root directly from an already located manager file, without searching.
+    ///
+    /// A bare file name like `yarn.lock` gives a root at `.`, the current directory.
+    pub fn from_manager_file(path: &Path) -> Result<Self> {
+        let manager = Manager::try_from(path)?;
+        if !path.try_exists()? {
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+
+        let path = manager_file_dir(path)
+            .ok_or_else(|| manager::InvalidFileError(path.to_path_buf()))?
+            .to_path_buf();
         Ok(Self { manager, path })
     }
 
     pub fn with_manager(cwd: impl AsRef<Path>, manager: Manager) -> io::Result<Self> {
-        let mut path = search_up(cwd, [&manager])?;
+        Ok(Self::search_manager(
+            cwd,
+            manager,
+            &SearchOptions::default(),
+        )?)
+    }
+
+    /// Finds the root as [`Root::new`] does, through any manager's file, but reports
+    /// `manager` instead of the detected one, e.g. for a `--manager pnpm` flag in a repo
+    /// that still has a `yarn.lock`.
+    ///
+    /// Unlike [`Root::with_manager`], this doesn't require `manager`'s own file to exist.
+    pub fn with_explicit_manager(cwd: impl AsRef<Path>, manager: Manager) -> Result<Self> {
+        Ok(Self::new(cwd)?.with_manager_override(manager))
+    }
+
+    /// Searches for the file of a single manager.
+    pub(crate) fn search_manager(
+        cwd: impl AsRef<Path>,
+        manager: Manager,
+        options: &SearchOptions,
+    ) -> Result<Self> {
+        reject_bare_repository(cwd.as_ref())?;
+        let mut path = search_up(cwd, &[manager], options)?;
         path.pop();
         Ok(Self { manager, path })
     }
+
+    /// Keeps this root's path but swaps its manager, e.g. when migrating from npm to pnpm.
+    ///
+    /// This does not touch the filesystem, so the new manager's files need not exist yet.
+    pub fn with_manager_override(self, manager: Manager) -> Root {
+        Root { manager, ..self }
+    }
+
+    /// Re-runs detection from this root's path, e.g. after `rm yarn.lock && pnpm import`
+    /// changed the manager. `self` is left as it was.
+    pub fn reload(&self) -> Result<Self> {
+        Self::new(&self.path)
+    }
+
+    pub fn manager(&self) -> Manager {
+        self.manager
+    }
+
+    /// Fails with [`RootError::UnexpectedManager`] unless the workspace uses `expected`,
+    /// e.g. to enforce in CI that a repository sticks to pnpm.
+    pub fn assert_manager(&self, expected: Manager) -> Result<()> {
+        if self.manager != expected {
+            return Err(RootError::UnexpectedManager {
+                expected,
+                found: self.manager,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The root's path with every symlink resolved. Unlike [`Root::path`], which is the
+    /// logical path the search started from, this touches the filesystem on each call.
+    pub fn canonical_path(&self) -> io::Result<PathBuf> {
+        self.path.canonicalize()
+    }
+
+    /// The manager's primary file in the root, e.g. `/repo/yarn.lock`.
+    pub fn manager_file(&self) -> PathBuf {
+        self.path.join(self.manager.primary_file())
+    }
+
+    /// The manager's primary file relative to the root, e.g. `yarn.lock`.
+    pub fn manager_file_relative(&self) -> &'static Path {
+        self.manager.primary_file()
+    }
+
+    /// A compact description for status lines, like `pnpm @ ./` or `Yarn @ packages/ui`,
+    /// with the root relative to `relative_to`, or absolute if it isn't under it.
+    pub fn spec(&self, relative_to: &Path) -> String {
+        let path = match self.path.strip_prefix(relative_to) {
+            Ok(path) if path.as_os_str().is_empty() => Path::new("./"),
+            Ok(path) => path,
+            Err(_) => &self.path,
+        };
+        format!("{} @ {}", self.manager.display_name(), path.display())
+    }
+
+    /// The `common/config/rush` directory of a Rush workspace, if present.
+    pub fn rush_common_dir(&self) -> Option<PathBuf> {
+        let dir = self.path.join("common/config/rush");
+        dir.is_dir().then_some(dir)
+    }
+
+    /// Builds a [`Command`] that runs the manager's binary with `subcommand` in the root.
+    pub fn command(&self, subcommand: &[&str]) -> Command {
+        let mut command = Command::new(self.manager.binary());
+        command.args(subcommand).current_dir(&self.path);
+        command
+    }
 }
 
-fn search_up(
-    cwd: impl AsRef<Path>,
-    files: impl IntoIterator<Item = impl AsRef<Path>>,
-) -> io::Result<PathBuf> {
-    // TODO: Are these conversions necessary and/or good? Should cwd be canonicalized?
-    let mut cwd = cwd.as_ref().to_path_buf();
-    let files: Vec<_> = files.into_iter().map(|p| p.as_ref().to_owned()).collect();
+impl AsRef<Path> for Root {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
 
-    loop {
-        for file in &files {
-            let candidate = cwd.join(file);
-            if candidate.exists() {
-                return Ok(candidate);
-            }
+/// Fails with [`RootError::BareRepository`] if `cwd` is a bare git repository, or holds
+/// one as its `.git`, since there's no working tree to find a workspace in and searching
+/// up would only find unrelated parents.
+fn reject_bare_repository(cwd: &Path) -> Result<()> {
+    for repo in [cwd.to_path_buf(), cwd.join(".git")] {
+        if repo.join("HEAD").is_file() && is_bare(&repo.join("config"))? {
+            return Err(RootError::BareRepository(repo));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the git config at `path` sets `core.bare`.
+fn is_bare(path: &Path) -> io::Result<bool> {
+    let config = match fs::read_to_string(path) {
+        Ok(config) => config,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(error),
+    };
+
+    let mut in_core = false;
+    for line in config.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[') {
+            in_core = section
+                .trim_end_matches(']')
+                .trim()
+                .eq_ignore_ascii_case("core");
+        } else if in_core
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim().eq_ignore_ascii_case("bare")
+        {
+            return Ok(value.trim().eq_ignore_ascii_case("true"));
+        }
+    }
+    Ok(false)
+}
+
+/// Finds the Rush root for a directory inside its `common/` folder, e.g. `common/temp`.
+fn enclosing_rush_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .filter(|ancestor| ancestor.file_name().is_some_and(|name| name == "common"))
+        .filter_map(Path::parent)
+        .find(|parent| parent.join(Manager::Rush.primary_file()).exists())
+        .map(Path::to_path_buf)
+}
+
+/// The directory containing the manager file at `path`, where a bare file name like
+/// `yarn.lock` is in the current directory.
+fn manager_file_dir(path: &Path) -> Option<&Path> {
+    path.parent().map(|dir| {
+        if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{
+        env::PREFERRED_WORKSPACE_MANAGER,
+        test_utils::{fixture, write},
+        workspace::manager::SEARCH_ORDER,
+    };
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn diagnostics() {
+        use miette::Diagnostic;
+
+        let error = RootError::from(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(error.code().unwrap().to_string(), "js_workspace::io");
+        assert!(
+            error
+                .help()
+                .unwrap()
+                .to_string()
+                .contains("PREFERRED_WORKSPACE_MANAGER")
+        );
+
+        let error = RootError::from("lolwut".parse::<Manager>().unwrap_err());
+        assert_eq!(error.code().unwrap().to_string(), "js_workspace::manager");
+    }
+
+    #[test]
+    fn new_or_default_detected() {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        let cwd = dir.path().join("packages/foo");
+        fs::create_dir_all(&cwd).unwrap();
+
+        let root = Root::new_or_default(&cwd).unwrap();
+        assert_eq!(root.manager, Manager::Pnpm);
+        assert_eq!(root.path, dir.path());
+    }
+
+    #[test]
+    fn new_or_default_defaulted() {
+        let dir = fixture(&[]);
+        let root = Root::new_or_default(dir.path()).unwrap();
+        assert_eq!(root.manager, Manager::Npm);
+        assert_eq!(root.path, dir.path());
+    }
+
+    #[test]
+    fn new_at_filesystem_root() {
+        let cwd = std::env::current_dir().unwrap();
+        let filesystem_root = cwd.ancestors().last().unwrap();
+        if SEARCH_ORDER
+            .iter()
+            .any(|manager| filesystem_root.join(manager).exists())
+        {
+            return; // Nothing to check on a machine with a workspace at its root.
         }
 
-        if !cwd.pop() {
-            return Err(io::Error::from(io::ErrorKind::NotFound));
+        let error = Root::new(filesystem_root).unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "No workspace found at the filesystem root {}",
+                filesystem_root.display()
+            )
+        );
+    }
+
+    #[test]
+    fn try_discover_found() {
+        let dir = fixture(&["yarn.lock", "packages/foo/package.json"]);
+
+        let root = Root::try_discover(dir.path().join("packages/foo")).unwrap();
+        assert_eq!(root, Some(Root::new(dir.path()).unwrap()));
+    }
+
+    #[test]
+    fn try_discover_not_found() {
+        let dir = fixture(&[]);
+        assert_eq!(Root::try_discover(dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn try_discover_io_error() {
+        // Unlike an unreadable file, a directory in place of the git config fails to read
+        // even when the tests run as root.
+        let dir = fixture(&["yarn.lock", ".git/HEAD"]);
+        fs::create_dir(dir.path().join(".git/config")).unwrap();
+
+        let error = Root::try_discover(dir.path()).unwrap_err();
+        assert!(matches!(error, RootError::Io(_)));
+        assert!(!error.is_not_found());
+    }
+
+    #[test]
+    fn new_from_parent_skips_cwd() {
+        let dir = fixture(&["pnpm-workspace.yaml", "packages/foo/package-lock.json"]);
+
+        let root = Root::new_from_parent(dir.path().join("packages/foo")).unwrap();
+        assert_eq!(root.manager(), Manager::Pnpm);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test_case("/" ; "filesystem root")]
+    #[test_case("" ; "empty")]
+    fn new_from_parent_without_parent(cwd: &str) {
+        let error = Root::new_from_parent(cwd).unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            format!("{cwd} has no parent to search from")
+        );
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn new_git_aware_tracked_but_absent() {
+        let dir = fixture(&["pnpm-workspace.yaml", "packages/foo/package.json"]);
+        git(dir.path(), &["init", "--quiet"]);
+        git(dir.path(), &["add", "."]);
+        // What a sparse checkout leaves of a tracked file.
+        fs::remove_file(dir.path().join("pnpm-workspace.yaml")).unwrap();
+
+        let cwd = dir.path().join("packages/foo");
+        assert!(Root::new(&cwd).unwrap_err().is_not_found());
+        let root = Root::new_git_aware(&cwd).unwrap();
+        assert_eq!(root.manager(), Manager::Pnpm);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test]
+    fn new_git_aware_prefers_working_tree() {
+        let dir = fixture(&["yarn.lock", "packages/foo/package-lock.json"]);
+        git(dir.path(), &["init", "--quiet"]);
+        git(dir.path(), &["add", "yarn.lock"]);
+
+        let root = Root::new_git_aware(dir.path().join("packages/foo")).unwrap();
+        assert_eq!(root.manager(), Manager::Npm);
+    }
+
+    #[test]
+    fn new_git_aware_outside_repository() {
+        let dir = fixture(&[]);
+        assert!(Root::new_git_aware(dir.path()).unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn new_first_of_returns_first_success() {
+        let empty = fixture(&[]);
+        let other = fixture(&[]);
+        let workspace = fixture(&["pnpm-workspace.yaml"]);
+        let cwds = [
+            empty.path().to_path_buf(),
+            other.path().to_path_buf(),
+            workspace.path().to_path_buf(),
+        ];
+
+        let root = Root::new_first_of(&cwds).unwrap();
+        assert_eq!(root.manager, Manager::Pnpm);
+        assert_eq!(root.path, workspace.path());
+    }
+
+    #[test]
+    fn new_first_of_reports_every_failure() {
+        let empty = fixture(&[]);
+        let other = fixture(&[]);
+        let cwds = [empty.path().to_path_buf(), other.path().to_path_buf()];
+
+        let RootError::AllFailed(failures) = Root::new_first_of(&cwds).unwrap_err() else {
+            panic!("expected every candidate to fail");
+        };
+        let failed: Vec<_> = failures.iter().map(|(cwd, _)| cwd.clone()).collect();
+        assert_eq!(failed, cwds);
+        assert!(failures.iter().all(|(_, error)| error.is_not_found()));
+    }
+
+    #[test]
+    fn new_with_offset_from_root() {
+        let dir = fixture(&["yarn.lock"]);
+        let (root, offset) = Root::new_with_offset(dir.path()).unwrap();
+        assert_eq!(root.path, dir.path());
+        assert_eq!(offset, PathBuf::new());
+    }
+
+    #[test]
+    fn new_with_offset_from_deep_subdirectory() {
+        let dir = fixture(&["yarn.lock"]);
+        let cwd = dir.path().join("packages/foo/src");
+        fs::create_dir_all(&cwd).unwrap();
+
+        let (root, offset) = Root::new_with_offset(&cwd).unwrap();
+        assert_eq!(root.manager, Manager::Yarn);
+        assert_eq!(root.path, dir.path());
+        assert_eq!(offset, Path::new("packages/foo/src"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn new_with_offset_from_relative_path_with_parent_components() {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/foo/package.json",
+            "packages/bar/package.json",
+        ]);
+        // A path from the current directory to the fixture, like `../../tmp/.tmpXYZ`.
+        let current_dir = std::env::current_dir().unwrap();
+        let mut relative: PathBuf = current_dir.components().skip(1).map(|_| "..").collect();
+        relative.push(dir.path().strip_prefix("/").unwrap());
+        let cwd = relative.join("packages/foo/../bar");
+
+        let (root, offset) = Root::new_with_offset(&cwd).unwrap();
+        assert_eq!(root.manager, Manager::Yarn);
+        assert_eq!(
+            root.canonical_path().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+        assert_eq!(offset, Path::new("packages/bar"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn new_with_offset_climbing_out_of_symlink() {
+        let outside = fixture(&["target/index.js"]);
+        let dir = fixture(&["yarn.lock"]);
+        std::os::unix::fs::symlink(outside.path().join("target"), dir.path().join("link")).unwrap();
+
+        let error = Root::new_with_offset(dir.path().join("link/..")).unwrap_err();
+        assert!(matches!(error, RootError::Io(e) if e.kind() == io::ErrorKind::InvalidInput));
+    }
+
+    #[test_case("apps/foo/src" ; "subproject")]
+    #[test_case("common/temp" ; "common temp")]
+    #[test_case("common/config/rush" ; "common config")]
+    fn new_rush_from_nested_directory(cwd: &str) {
+        let dir = fixture(&[
+            "rush.json",
+            "apps/foo/package.json",
+            "common/config/rush/pnpm-lock.yaml",
+            "common/config/rush/yarn.lock",
+            "common/temp/pnpm-workspace.yaml",
+        ]);
+        let cwd = dir.path().join(cwd);
+        fs::create_dir_all(&cwd).unwrap();
+
+        let root = Root::new(&cwd).unwrap();
+        assert_eq!(root.manager, Manager::Rush);
+        assert_eq!(root.path, dir.path());
+        assert_eq!(
+            root.rush_common_dir(),
+            Some(dir.path().join("common/config/rush"))
+        );
+    }
+
+    #[test]
+    fn new_common_directory_without_rush() {
+        let dir = fixture(&["common/yarn.lock"]);
+        let root = Root::new(dir.path().join("common")).unwrap();
+        assert_eq!(root.manager, Manager::Yarn);
+        assert_eq!(root.path, dir.path().join("common"));
+        assert_eq!(root.rush_common_dir(), None);
+    }
+
+    #[test]
+    fn command_runs_in_root() {
+        let root = Root {
+            manager: Manager::Pnpm,
+            path: PathBuf::from("/repo"),
+        };
+
+        let command = root.command(&["install", "--frozen-lockfile"]);
+        assert_eq!(command.get_program(), "pnpm");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["install", "--frozen-lockfile"]
+        );
+        assert_eq!(command.get_current_dir(), Some(Path::new("/repo")));
+    }
+
+    #[test_case(&[Manager::Yarn], Manager::Yarn, "" ; "skips other managers")]
+    #[test_case(&[Manager::Npm, Manager::Yarn], Manager::Npm, "packages/foo" ; "nearest allowed")]
+    #[test_case(&[Manager::Yarn, Manager::Yarn], Manager::Yarn, "" ; "duplicates")]
+    fn new_restricted(allowed: &[Manager], expected: Manager, expected_dir: &str) {
+        let dir = fixture(&["yarn.lock", "packages/foo/package-lock.json"]);
+
+        let root = Root::new_restricted(dir.path().join("packages/foo"), allowed).unwrap();
+        assert_eq!(root.manager(), expected);
+        assert_eq!(root.path(), dir.path().join(expected_dir));
+    }
+
+    #[test]
+    fn new_restricted_same_directory_keeps_precedence() {
+        let dir = fixture(&["yarn.lock", "package-lock.json"]);
+        let root = Root::new_restricted(dir.path(), &[Manager::Npm, Manager::Yarn]).unwrap();
+        assert_eq!(root.manager(), Manager::Yarn);
+    }
+
+    #[test]
+    fn new_restricted_to_nothing() {
+        let dir = fixture(&["yarn.lock"]);
+        let error = Root::new_restricted(dir.path(), &[]).unwrap_err();
+        assert!(error.is_not_found());
+    }
+
+    #[test]
+    fn from_manager_file() {
+        let dir = fixture(&["packages/foo/package-lock.json"]);
+        let file = dir.path().join("packages/foo/package-lock.json");
+
+        let root = Root::from_manager_file(&file).unwrap();
+        assert_eq!(root.manager, Manager::Npm);
+        assert_eq!(root.path, dir.path().join("packages/foo"));
+    }
+
+    #[test]
+    fn from_manager_file_not_a_manager_file() {
+        let dir = fixture(&["package.json"]);
+        let error = Root::from_manager_file(&dir.path().join("package.json")).unwrap_err();
+        assert!(matches!(error, RootError::Manager(_)));
+    }
+
+    #[test]
+    fn from_manager_file_missing() {
+        let dir = fixture(&[]);
+        let error = Root::from_manager_file(&dir.path().join("yarn.lock")).unwrap_err();
+        assert!(matches!(error, RootError::Io(e) if e.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn from_manager_file_filesystem_root() {
+        let error = Root::from_manager_file(Path::new("/")).unwrap_err();
+        assert!(matches!(error, RootError::Manager(_)));
+    }
+
+    #[test_case("/repo/yarn.lock", Some("/repo") ; "absolute")]
+    #[test_case("repo/yarn.lock", Some("repo") ; "relative")]
+    #[test_case("yarn.lock", Some(".") ; "bare name")]
+    #[test_case("/", None ; "without parent")]
+    fn manager_file_dir(path: &str, expected: Option<&str>) {
+        assert_eq!(
+            super::manager_file_dir(Path::new(path)),
+            expected.map(Path::new)
+        );
+    }
+
+    #[test]
+    fn as_ref_path() {
+        fn root_dir(path: impl AsRef<Path>) -> PathBuf {
+            path.as_ref().to_path_buf()
         }
+
+        let root = Root {
+            manager: Manager::Yarn,
+            path: PathBuf::from("/repo"),
+        };
+        assert_eq!(root_dir(&root), Path::new("/repo"));
+    }
+
+    #[test]
+    fn new_with_registry_detects_custom_file_name() {
+        let dir = fixture(&["yarn.lock.bak"]);
+        let mut registry = ManagerRegistry::new();
+        registry.register(Manager::Yarn, "yarn.lock.bak");
+
+        let root = Root::new_with_registry(dir.path(), &registry).unwrap();
+        assert_eq!(root.manager, Manager::Yarn);
+        assert_eq!(root.path, dir.path());
+    }
+
+    #[test]
+    fn new_with_registry_detects_nested_custom_path() {
+        let dir = fixture(&["config/yarn.lock.bak", "packages/foo/package.json"]);
+        let mut registry = ManagerRegistry::new();
+        registry.register(Manager::Yarn, "config/yarn.lock.bak");
+
+        let root = Root::new_with_registry(dir.path().join("packages/foo"), &registry).unwrap();
+        assert_eq!(root.manager, Manager::Yarn);
+        assert_eq!(root.path, dir.path());
+    }
+
+    #[test]
+    fn new_with_registry_defers_to_rush() {
+        let dir = fixture(&["rush.json", "common/temp/yarn.lock.bak"]);
+        let mut registry = ManagerRegistry::new();
+        registry.register(Manager::Yarn, "yarn.lock.bak");
+
+        let root = Root::new_with_registry(dir.path().join("common/temp"), &registry).unwrap();
+        assert_eq!(root.manager, Manager::Rush);
+        assert_eq!(root.path, dir.path());
+    }
+
+    #[test]
+    fn new_with_registry_rejects_bare_repository() {
+        let dir = fixture(&["HEAD", "yarn.lock"]);
+        write(dir.path(), "config", "[core]\n\tbare = true\n");
+
+        let error = Root::new_with_registry(dir.path(), &ManagerRegistry::new()).unwrap_err();
+        assert!(matches!(error, RootError::BareRepository(_)));
+    }
+
+    #[test]
+    fn with_explicit_manager_overrides_detection() {
+        let dir = fixture(&["yarn.lock", "packages/foo/package.json"]);
+
+        let root =
+            Root::with_explicit_manager(dir.path().join("packages/foo"), Manager::Pnpm).unwrap();
+        assert_eq!(root.manager(), Manager::Pnpm);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test]
+    fn with_explicit_manager_not_found() {
+        let dir = fixture(&[]);
+        let error = Root::with_explicit_manager(dir.path(), Manager::Pnpm).unwrap_err();
+        assert!(error.is_not_found());
+    }
+
+    #[test]
+    fn with_manager_override_keeps_path() {
+        let root = Root {
+            manager: Manager::Npm,
+            path: PathBuf::from("/repo"),
+        };
+
+        let root = root.with_manager_override(Manager::Pnpm);
+        assert_eq!(root.manager(), Manager::Pnpm);
+        assert_eq!(root.path(), Path::new("/repo"));
+    }
+
+    #[test]
+    fn reload_reflects_new_manager() {
+        let dir = fixture(&["yarn.lock"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.manager(), Manager::Yarn);
+
+        fs::remove_file(dir.path().join("yarn.lock")).unwrap();
+        write(dir.path(), "pnpm-workspace.yaml", "packages: []\n");
+
+        let reloaded = root.reload().unwrap();
+        assert_eq!(reloaded.manager(), Manager::Pnpm);
+        assert_eq!(reloaded.path(), root.path());
+        assert_eq!(root.manager(), Manager::Yarn);
+    }
+
+    #[test]
+    fn assert_manager_matching() {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.assert_manager(Manager::Pnpm).is_ok());
+    }
+
+    #[test]
+    fn assert_manager_mismatch() {
+        let dir = fixture(&["yarn.lock"]);
+        let root = Root::new(dir.path()).unwrap();
+
+        let error = root.assert_manager(Manager::Pnpm).unwrap_err();
+        assert!(matches!(
+            error,
+            RootError::UnexpectedManager {
+                expected: Manager::Pnpm,
+                found: Manager::Yarn,
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Expected a Pnpm workspace, but found a Yarn workspace"
+        );
+    }
+
+    #[test]
+    fn into_io_error_preserves_io_kind() {
+        let error = RootError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(
+            io::Error::from(error).kind(),
+            io::ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn into_io_error_wraps_others() {
+        let error = io::Error::from(RootError::Manager(String::from("Invalid manager: lolwut")));
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(error.to_string(), "Invalid manager: lolwut");
+    }
+
+    #[test]
+    fn new_within_repo_stops_at_submodule() {
+        let dir = fixture(&[
+            ".git/HEAD",
+            "yarn.lock",
+            "libs/sub/pnpm-workspace.yaml",
+            "libs/sub/packages/foo/index.js",
+            "libs/bare/index.js",
+        ]);
+        write(
+            dir.path(),
+            "libs/sub/.git",
+            "gitdir: ../../.git/modules/sub\n",
+        );
+        write(
+            dir.path(),
+            "libs/bare/.git",
+            "gitdir: ../../.git/modules/bare\n",
+        );
+
+        let root = Root::new_within_repo(dir.path().join("libs/sub/packages/foo")).unwrap();
+        assert_eq!(root.manager(), Manager::Pnpm);
+        assert_eq!(root.path(), dir.path().join("libs/sub"));
+
+        let error = Root::new_within_repo(dir.path().join("libs/bare")).unwrap_err();
+        assert!(error.is_not_found());
+    }
+
+    #[test_case("repo.git", "repo.git" ; "bare clone")]
+    #[test_case("repo", "repo/.git" ; "bare dot git")]
+    fn new_in_bare_repository(cwd: &str, repo: &str) {
+        let dir = fixture(&["yarn.lock"]);
+        let repo = dir.path().join(repo);
+        write(&repo, "HEAD", "ref: refs/heads/main\n");
+        write(
+            &repo,
+            "config",
+            "[core]\n\trepositoryformatversion = 0\n\tbare = true\n",
+        );
+
+        let error = Root::new(dir.path().join(cwd)).unwrap_err();
+        assert!(matches!(error, RootError::BareRepository(path) if path == repo));
+    }
+
+    #[test]
+    fn new_in_non_bare_repository() {
+        let dir = fixture(&["yarn.lock", ".git/HEAD"]);
+        write(dir.path(), ".git/config", "[core]\n\tbare = false\n");
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.manager(), Manager::Yarn);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn canonical_path_resolves_symlinked_root() {
+        let dir = fixture(&["real/yarn.lock"]);
+        // Canonicalize, as the temporary directory may itself be behind a symlink.
+        let base = dir.path().canonicalize().unwrap();
+        std::os::unix::fs::symlink(base.join("real"), base.join("link")).unwrap();
+
+        let root = Root::new(base.join("link")).unwrap();
+        assert_eq!(root.path(), base.join("link"));
+        assert_eq!(root.canonical_path().unwrap(), base.join("real"));
+    }
+
+    #[test_case(Manager::Yarn ; "yarn")]
+    #[test_case(Manager::Pnpm ; "pnpm")]
+    #[test_case(Manager::Rush ; "rush")]
+    #[test_case(Manager::Npm ; "npm")]
+    #[test_case(Manager::Lerna ; "lerna")]
+    fn manager_file_relative(manager: Manager) {
+        let root = Root {
+            manager,
+            path: PathBuf::from("/repo"),
+        };
+        assert_eq!(
+            root.manager_file_relative(),
+            AsRef::<Path>::as_ref(&manager)
+        );
+        assert_eq!(
+            root.path().join(root.manager_file_relative()),
+            root.manager_file()
+        );
+    }
+
+    #[test_case(Manager::Pnpm, "/repo", "/repo", "pnpm @ ./" ; "at base")]
+    #[test_case(Manager::Yarn, "/repo/packages/ui", "/repo", "Yarn @ packages/ui" ; "under base")]
+    #[test_case(Manager::Npm, "/other/app", "/repo", "npm @ /other/app" ; "outside base")]
+    fn spec(manager: Manager, path: &str, relative_to: &str, expected: &str) {
+        let root = Root {
+            manager,
+            path: PathBuf::from(path),
+        };
+        assert_eq!(root.spec(Path::new(relative_to)), expected);
+    }
+
+    #[test]
+    fn debug_includes_manager_file() {
+        let root = Root {
+            manager: Manager::Yarn,
+            path: PathBuf::from("/repo"),
+        };
+        assert_eq!(
+            format!("{root:?}"),
+            r#"Root { manager: Yarn, path: "/repo", file: "/repo/yarn.lock" }"#
+        );
+    }
+
+    #[test_case(r#"{ "name": "foo" }"#, Manager::Npm ; "defaults to npm")]
+    #[test_case(r#"{ "packageManager": "pnpm@9.1.0" }"#, Manager::Pnpm ; "package manager field")]
+    fn new_lenient_package_json_only(package_json: &str, expected: Manager) {
+        let dir = fixture(&["src/index.js"]);
+        write(dir.path(), "package.json", package_json);
+
+        assert!(Root::new(dir.path()).unwrap_err().is_not_found());
+        let root = Root::new_lenient(dir.path().join("src")).unwrap();
+        assert_eq!(root.manager(), expected);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test]
+    fn new_lenient_prefers_manager_files() {
+        let dir = fixture(&["yarn.lock", "packages/foo/package.json"]);
+        let root = Root::new_lenient(dir.path().join("packages/foo")).unwrap();
+        assert_eq!(root.manager(), Manager::Yarn);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test_case(&[], Manager::Yarn ; "unset")]
+    #[test_case(&[(PREFERRED_WORKSPACE_MANAGER, "npm")], Manager::Npm ; "npm")]
+    #[test_case(&[(PREFERRED_WORKSPACE_MANAGER, "PNPM")], Manager::Pnpm ; "case insensitive")]
+    #[test_case(&[("UNRELATED", "pnpm")], Manager::Yarn ; "other variable")]
+    fn new_with_env(vars: &[(&str, &str)], expected: Manager) {
+        let dir = fixture(&["yarn.lock", "package-lock.json", "pnpm-workspace.yaml"]);
+        let env: HashMap<_, _> = vars.iter().copied().collect();
+
+        let root = Root::new_with_env(dir.path(), &env).unwrap();
+        assert_eq!(root.manager(), expected);
+        assert_eq!(root.path(), dir.path());
+    }
+
+    #[test]
+    fn new_with_env_invalid_manager() {
+        let dir = fixture(&["yarn.lock"]);
+        let env = HashMap::from([(PREFERRED_WORKSPACE_MANAGER, "lolwut")]);
+
+        let error = Root::new_with_env(dir.path(), &env).unwrap_err();
+        assert!(matches!(error, RootError::Manager(_)));
     }
 }
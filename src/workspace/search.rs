@@ -0,0 +1,681 @@
+use std::{
+    borrow::Cow,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    manager::{MANIFEST_FIRST_ORDER, Manager, SEARCH_ORDER},
+    root::RootError,
+};
+
+/// The most directories [`search_up`] visits, far deeper than any real directory tree.
+pub(crate) const MAX_ANCESTORS: usize = 1024;
+
+/// Whether the upward search may pass through symbolic links.
+///
+/// Following symlinks means a workspace can be detected from files that physically live
+/// outside of the directory tree `cwd` is in. When `cwd` is reached through a symlink,
+/// e.g. `sandbox/link/sub` with `link` pointing outside the sandbox, the lexical parent of
+/// `link` is `sandbox`, but physically `sandbox` isn't a parent of `sub` at all. With
+/// [`FollowSymlinks::Always`], the search climbs into `sandbox` regardless, and a symlinked
+/// manager file may point anywhere, so untrusted trees should be searched with
+/// [`FollowSymlinks::Never`].
+///
+/// Without following, the search still covers the tree `cwd` physically lives in, up to
+/// the deepest symlinked ancestor of `cwd`, but never climbs past that symlink. So a `cwd`
+/// under a symlinked prefix, like `/tmp` on macOS or a symlinked home directory, is
+/// searched as usual, only stopping at the prefix rather than continuing to `/`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FollowSymlinks {
+    /// Climb past symlinked directories and accept symlinked manager files.
+    #[default]
+    Always,
+    /// Stop at the deepest symlinked ancestor of `cwd` and reject symlinked manager files.
+    Never,
+    /// Stop at the deepest symlinked ancestor of `cwd`, but accept symlinked manager files.
+    FilesOnly,
+}
+
+/// Whether manager file names must match exactly, e.g. whether `Yarn.lock` counts as
+/// `yarn.lock`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    /// Follow the platform. Searches leave it to the filesystem, which matches exactly on
+    /// most Linux filesystems and ignores case on default macOS and Windows volumes.
+    #[default]
+    Auto,
+    /// Only match the exact file name, even on a case-insensitive filesystem.
+    Sensitive,
+    /// Match file names regardless of ASCII case, even on a case-sensitive filesystem.
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    /// Whether names differing only in ASCII case match.
+    pub fn ignores_case(&self) -> bool {
+        match self {
+            CaseSensitivity::Auto => cfg!(any(windows, target_os = "macos")),
+            CaseSensitivity::Sensitive => false,
+            CaseSensitivity::Insensitive => true,
+        }
+    }
+}
+
+/// Which manager wins when the files of several are in the same directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precedence {
+    /// Orchestrators first, then yarn, pnpm, and npm, so e.g. a `yarn.lock` beats a
+    /// `pnpm-workspace.yaml`.
+    #[default]
+    SearchOrder,
+    /// Managers identified by a workspace manifest like `pnpm-workspace.yaml` or
+    /// `lerna.json` beat those identified by a bare lockfile like `yarn.lock`.
+    ManifestFirst,
+}
+
+impl Precedence {
+    /// Every manager, in the order their files are probed in each directory.
+    pub(crate) fn order(&self) -> &'static [Manager] {
+        match self {
+            Precedence::SearchOrder => SEARCH_ORDER,
+            Precedence::ManifestFirst => MANIFEST_FIRST_ORDER,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SearchOptions {
+    pub(crate) follow_symlinks: FollowSymlinks,
+    pub(crate) case_sensitivity: CaseSensitivity,
+    /// Stop after the first directory containing `.git`, the root of a repository or submodule.
+    pub(crate) stop_at_repo: bool,
+    /// Stop after the first directory containing `Cargo.toml`, the root of a Rust project.
+    pub(crate) stop_at_cargo: bool,
+    pub(crate) precedence: Precedence,
+}
+
+impl SearchOptions {
+    /// The files marking a directory the search must not ascend past.
+    fn boundaries(&self) -> &'static [&'static str] {
+        match (self.stop_at_repo, self.stop_at_cargo) {
+            (false, false) => &[],
+            (true, false) => &[".git"],
+            (false, true) => &["Cargo.toml"],
+            (true, true) => &[".git", "Cargo.toml"],
+        }
+    }
+}
+
+/// What a [`SearchState`] needs next.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Step<'a> {
+    /// Probe whether this manager file exists, then call [`SearchState::advance`].
+    Probe(&'a Path),
+    /// Probe whether this boundary marker, like `.git`, exists, then call
+    /// [`SearchState::advance`].
+    ProbeBoundary(&'a Path),
+    /// The search is over and found this manager file.
+    Found(&'a Path),
+    /// The search is over without finding anything.
+    NotFound,
+    /// The search is over after giving up at [`MAX_ANCESTORS`].
+    TooDeep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Probing the file at this index in the current directory.
+    File(usize),
+    /// Probing for the boundary marker at this index in the current directory.
+    Boundary(usize),
+    Found,
+    NotFound,
+    TooDeep,
+}
+
+/// The upward search without any IO: it names each path to probe, and the caller
+/// reports back whether that path exists.
+#[derive(Debug)]
+pub(crate) struct SearchState<'f, P> {
+    dir: PathBuf,
+    files: &'f [P],
+    /// The search ends after probing this directory rather than ascending past it.
+    stop_at: Option<PathBuf>,
+    /// Files marking a directory the search must not ascend past.
+    boundaries: &'f [&'f str],
+    phase: Phase,
+    ascended: usize,
+    // Reused for every probe to avoid allocating a new path per candidate.
+    candidate: PathBuf,
+}
+
+impl<'f, P: AsRef<Path>> SearchState<'f, P> {
+    pub(crate) fn new(
+        cwd: PathBuf,
+        files: &'f [P],
+        stop_at: Option<PathBuf>,
+        boundaries: &'f [&'f str],
+    ) -> Self {
+        let candidate = PathBuf::with_capacity(cwd.as_os_str().len() + 32);
+        Self {
+            dir: cwd,
+            files,
+            stop_at,
+            boundaries,
+            phase: Phase::File(0),
+            ascended: 0,
+            candidate,
+        }
+    }
+
+    /// The next thing the search needs, which stays the same until [`SearchState::advance`].
+    pub(crate) fn poll(&mut self) -> Step<'_> {
+        self.settle();
+        match self.phase {
+            Phase::File(_) => Step::Probe(&self.candidate),
+            Phase::Boundary(_) => Step::ProbeBoundary(&self.candidate),
+            Phase::Found => Step::Found(&self.candidate),
+            Phase::NotFound => Step::NotFound,
+            Phase::TooDeep => Step::TooDeep,
+        }
+    }
+
+    /// Reports whether the path of the last [`Step::Probe`] or [`Step::ProbeBoundary`]
+    /// exists. Does nothing once the search is over.
+    pub(crate) fn advance(&mut self, exists: bool) {
+        self.phase = match (self.phase, exists) {
+            (Phase::File(_), true) => Phase::Found,
+            (Phase::File(index), false) => Phase::File(index + 1),
+            (Phase::Boundary(_), true) => Phase::NotFound,
+            (Phase::Boundary(index), false) => Phase::Boundary(index + 1),
+            (phase, _) => phase,
+        };
+    }
+
+    /// Moves on to the next phase that needs a probe, and points `candidate` at it.
+    fn settle(&mut self) {
+        loop {
+            match self.phase {
+                Phase::File(index) => match self.files.get(index) {
+                    Some(file) => {
+                        self.set_candidate(file.as_ref());
+                        return;
+                    }
+                    None => self.phase = Phase::Boundary(0),
+                },
+                Phase::Boundary(index) => match self.boundaries.get(index) {
+                    Some(boundary) => {
+                        self.set_candidate(Path::new(boundary));
+                        return;
+                    }
+                    None => self.phase = self.ascend(),
+                },
+                Phase::Found | Phase::NotFound | Phase::TooDeep => return,
+            }
+        }
+    }
+
+    fn set_candidate(&mut self, file: &Path) {
+        self.candidate.as_mut_os_string().clear();
+        self.candidate.push(&self.dir);
+        self.candidate.push(file);
+    }
+
+    fn ascend(&mut self) -> Phase {
+        if self.stop_at.as_ref() == Some(&self.dir) {
+            return Phase::NotFound;
+        }
+        // `pop` keeps any prefix, so it stops at a drive root like `C:\`, an extended-length
+        // root like `\\?\C:\`, or a UNC share root like `\\server\share\`.
+        if !self.dir.pop() {
+            return Phase::NotFound;
+        }
+        // `pop` always terminates for ordinary paths; the cap guards against pathological ones.
+        self.ascended += 1;
+        if self.ascended == MAX_ANCESTORS {
+            return Phase::TooDeep;
+        }
+        Phase::File(0)
+    }
+}
+
+pub(crate) fn search_up<P: AsRef<Path>>(
+    cwd: impl AsRef<Path>,
+    files: &[P],
+    options: &SearchOptions,
+) -> Result<PathBuf, RootError> {
+    // TODO: Should cwd be canonicalized?
+    let cwd = cwd.as_ref();
+
+    // Climbing past a symlinked directory leaves the tree cwd physically lives in.
+    let stop_at = match options.follow_symlinks {
+        FollowSymlinks::Always => None,
+        FollowSymlinks::Never | FollowSymlinks::FilesOnly => deepest_symlink(cwd),
+    };
+
+    let mut state = SearchState::new(cwd.to_path_buf(), files, stop_at, options.boundaries());
+    loop {
+        let exists = match state.poll() {
+            Step::Probe(candidate) => probe(candidate, options),
+            // Any kind of entry counts, e.g. the `.git` file of a submodule or worktree.
+            Step::ProbeBoundary(boundary) => boundary.exists(),
+            Step::Found(path) => return Ok(path.to_path_buf()),
+            Step::NotFound => return Err(not_found(cwd, &state.dir).into()),
+            Step::TooDeep => return Err(RootError::TooDeep(cwd.to_path_buf())),
+        };
+        state.advance(exists);
+    }
+}
+
+/// A `NotFound` error saying where the search up from `cwd` gave up, having last
+/// searched `dir`.
+fn not_found(cwd: &Path, dir: &Path) -> io::Error {
+    let message = if dir.as_os_str().is_empty() {
+        format!(
+            "No workspace found in {} or any of its ancestors",
+            cwd.display()
+        )
+    } else if dir.parent().is_some() {
+        format!(
+            "No workspace found in {} or any of its ancestors within {}",
+            cwd.display(),
+            dir.display()
+        )
+    } else if dir == cwd {
+        format!(
+            "No workspace found at the filesystem root {}",
+            cwd.display()
+        )
+    } else {
+        format!(
+            "No workspace found in {} or any of its ancestors, up to the filesystem root {}",
+            cwd.display(),
+            dir.display()
+        )
+    };
+    io::Error::new(io::ErrorKind::NotFound, message)
+}
+
+fn probe(candidate: &Path, options: &SearchOptions) -> bool {
+    let found = match options.case_sensitivity {
+        CaseSensitivity::Auto => Cow::Borrowed(candidate),
+        case_sensitivity => match find_entry(candidate, case_sensitivity.ignores_case()) {
+            Some(found) => Cow::Owned(found),
+            None => return false,
+        },
+    };
+
+    match options.follow_symlinks {
+        FollowSymlinks::Never => found
+            .symlink_metadata()
+            .is_ok_and(|meta| !meta.file_type().is_symlink()),
+        FollowSymlinks::Always | FollowSymlinks::FilesOnly => found.exists(),
+    }
+}
+
+/// The entry of `candidate`'s directory named like `candidate`, looked up by listing the
+/// directory since the filesystem's own lookup may or may not ignore case.
+fn find_entry(candidate: &Path, ignore_case: bool) -> Option<PathBuf> {
+    let name = candidate.file_name()?;
+    let dir = candidate.parent()?;
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let entry = entry.file_name();
+            if ignore_case {
+                entry.eq_ignore_ascii_case(name)
+            } else {
+                entry == name
+            }
+        })
+        .map(|entry| entry.path())
+}
+
+/// The ancestor of `dir`, or `dir` itself, closest to `dir` that is a symlink.
+fn deepest_symlink(dir: &Path) -> Option<PathBuf> {
+    // A path spelled the same once resolved has no symlinks to look for.
+    if dir.canonicalize().is_ok_and(|canonical| canonical == dir) {
+        return None;
+    }
+    dir.ancestors()
+        .find(|ancestor| {
+            ancestor
+                .symlink_metadata()
+                .is_ok_and(|meta| meta.file_type().is_symlink())
+        })
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    /// The straightforward implementation that `search_up` must stay equivalent to.
+    fn naive_search_up(cwd: &Path, files: &[&str]) -> io::Result<PathBuf> {
+        let mut cwd = cwd.to_path_buf();
+        loop {
+            for file in files {
+                let candidate = cwd.join(file);
+                if candidate.exists() {
+                    return Ok(candidate);
+                }
+            }
+            if !cwd.pop() {
+                return Err(io::Error::from(io::ErrorKind::NotFound));
+            }
+        }
+    }
+
+    #[test_case("", &["yarn.lock"] ; "in cwd")]
+    #[test_case("packages/foo/src", &["yarn.lock"] ; "from subdirectory")]
+    #[test_case("packages/foo/src", &["lerna.json", "package-lock.json", "yarn.lock"] ; "nearest wins")]
+    #[test_case("packages/foo/src", &["config/yarn.lock", "yarn.lock"] ; "nested candidate")]
+    #[test_case("packages/foo/src", &["missing.json"] ; "not found")]
+    fn search_up_matches_naive_search(cwd: &str, files: &[&str]) {
+        let dir = fixture(&[
+            "yarn.lock",
+            "packages/foo/package-lock.json",
+            "config/yarn.lock",
+        ]);
+        let cwd = dir.path().join(cwd);
+        fs::create_dir_all(&cwd).unwrap();
+
+        let actual =
+            search_up(&cwd, files, &SearchOptions::default()).map_err(|e| e.is_not_found());
+        let expected =
+            naive_search_up(&cwd, files).map_err(|e| e.kind() == io::ErrorKind::NotFound);
+        assert_eq!(actual, expected);
+    }
+
+    /// Drives a [`SearchState`] with scripted probe results, recording every step.
+    fn drive(mut state: SearchState<'_, &str>, existing: &[&str]) -> Vec<String> {
+        let mut steps = Vec::new();
+        loop {
+            let step = state.poll();
+            steps.push(format!("{step:?}"));
+            let exists = match step {
+                Step::Probe(path) | Step::ProbeBoundary(path) => {
+                    existing.iter().any(|existing| path == Path::new(existing))
+                }
+                Step::Found(_) | Step::NotFound | Step::TooDeep => return steps,
+            };
+            state.advance(exists);
+        }
+    }
+
+    #[test]
+    fn search_state_finds_nearest() {
+        let state = SearchState::new(PathBuf::from("/a/b"), &["x", "y"], None, &[]);
+        assert_eq!(
+            drive(state, &["/a/y", "/x"]),
+            [
+                r#"Probe("/a/b/x")"#,
+                r#"Probe("/a/b/y")"#,
+                r#"Probe("/a/x")"#,
+                r#"Probe("/a/y")"#,
+                r#"Found("/a/y")"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn search_state_not_found_at_filesystem_root() {
+        let state = SearchState::new(PathBuf::from("/a"), &["x"], None, &[]);
+        assert_eq!(
+            drive(state, &[]),
+            [r#"Probe("/a/x")"#, r#"Probe("/x")"#, "NotFound"]
+        );
+    }
+
+    #[test]
+    fn search_state_stops_at_repo_boundary() {
+        let state = SearchState::new(PathBuf::from("/repo/sub"), &["x"], None, &[".git"]);
+        assert_eq!(
+            drive(state, &["/repo/.git", "/x"]),
+            [
+                r#"Probe("/repo/sub/x")"#,
+                r#"ProbeBoundary("/repo/sub/.git")"#,
+                r#"Probe("/repo/x")"#,
+                r#"ProbeBoundary("/repo/.git")"#,
+                "NotFound",
+            ]
+        );
+    }
+
+    #[test_case("/", "/", "No workspace found at the filesystem root /" ; "at filesystem root")]
+    #[test_case(
+        "/a/b",
+        "/",
+        "No workspace found in /a/b or any of its ancestors, up to the filesystem root /" ;
+        "below filesystem root"
+    )]
+    #[test_case(
+        "/repo/sub",
+        "/repo",
+        "No workspace found in /repo/sub or any of its ancestors within /repo" ;
+        "boundary"
+    )]
+    #[test_case("a/b", "", "No workspace found in a/b or any of its ancestors" ; "relative")]
+    fn not_found_message(cwd: &str, dir: &str, expected: &str) {
+        let error = not_found(Path::new(cwd), Path::new(dir));
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn search_state_probes_every_boundary() {
+        let state = SearchState::new(
+            PathBuf::from("/rust/web"),
+            &["x"],
+            None,
+            &[".git", "Cargo.toml"],
+        );
+        assert_eq!(
+            drive(state, &["/rust/Cargo.toml", "/x"]),
+            [
+                r#"Probe("/rust/web/x")"#,
+                r#"ProbeBoundary("/rust/web/.git")"#,
+                r#"ProbeBoundary("/rust/web/Cargo.toml")"#,
+                r#"Probe("/rust/x")"#,
+                r#"ProbeBoundary("/rust/.git")"#,
+                r#"ProbeBoundary("/rust/Cargo.toml")"#,
+                "NotFound",
+            ]
+        );
+    }
+
+    #[test]
+    fn search_state_stops_at_symlink() {
+        let stop_at = Some(PathBuf::from("/a/link"));
+        let state = SearchState::new(PathBuf::from("/a/link/b"), &["x"], stop_at, &[]);
+        assert_eq!(
+            drive(state, &["/a/x"]),
+            [
+                r#"Probe("/a/link/b/x")"#,
+                r#"Probe("/a/link/x")"#,
+                "NotFound"
+            ]
+        );
+    }
+
+    #[test]
+    fn search_state_too_deep() {
+        let deep: PathBuf = std::iter::repeat_n("d", MAX_ANCESTORS + 1).collect();
+        let steps = drive(
+            SearchState::new(Path::new("/").join(deep), &["x"], None, &[]),
+            &[],
+        );
+        assert_eq!(steps.len(), MAX_ANCESTORS + 1);
+        assert_eq!(steps.last().unwrap(), "TooDeep");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn search_state_stops_at_extended_length_drive_root() {
+        let state = SearchState::new(PathBuf::from(r"\\?\C:\foo\bar"), &["x"], None, &[]);
+        assert_eq!(
+            drive(state, &[]),
+            [
+                r#"Probe("\\\\?\\C:\\foo\\bar\\x")"#,
+                r#"Probe("\\\\?\\C:\\foo\\x")"#,
+                r#"Probe("\\\\?\\C:\\x")"#,
+                "NotFound",
+            ]
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn search_state_stops_at_unc_share_root() {
+        let state = SearchState::new(PathBuf::from(r"\\server\share\foo"), &["x"], None, &[]);
+        assert_eq!(
+            drive(state, &[]),
+            [
+                r#"Probe("\\\\server\\share\\foo\\x")"#,
+                r#"Probe("\\\\server\\share\\x")"#,
+                "NotFound",
+            ]
+        );
+    }
+
+    #[cfg(windows)]
+    #[test_case(r"\\?\C:\js-workspace-missing\foo\bar" ; "extended length")]
+    #[test_case(r"\\localhost\js-workspace-missing\foo" ; "unc")]
+    fn search_up_windows_prefix_not_found(cwd: &str) {
+        let error = search_up(
+            cwd,
+            &["js-workspace-missing.lock"],
+            &SearchOptions::default(),
+        );
+        assert!(error.unwrap_err().is_not_found());
+    }
+
+    #[test_case(CaseSensitivity::Sensitive, false ; "sensitive")]
+    #[test_case(CaseSensitivity::Insensitive, true ; "insensitive")]
+    fn search_up_mixed_case(case_sensitivity: CaseSensitivity, found: bool) {
+        let dir = fixture(&["Yarn.lock", "packages/foo/index.js"]);
+        let options = SearchOptions {
+            case_sensitivity,
+            ..SearchOptions::default()
+        };
+
+        let actual = search_up(dir.path().join("packages/foo"), &["yarn.lock"], &options);
+        match found {
+            true => assert_eq!(actual.unwrap(), dir.path().join("yarn.lock")),
+            false => assert!(actual.unwrap_err().is_not_found()),
+        }
+    }
+
+    #[test]
+    fn search_up_mixed_case_auto() {
+        let dir = fixture(&["Yarn.lock"]);
+        // Whether this matches is up to the filesystem the test runs on.
+        let expected = dir.path().join("yarn.lock").exists();
+
+        let actual = search_up(dir.path(), &["yarn.lock"], &SearchOptions::default());
+        assert_eq!(actual.is_ok(), expected);
+    }
+
+    #[test]
+    fn search_up_deep_tree_under_cap() {
+        let deep: PathBuf = std::iter::repeat_n("d", MAX_ANCESTORS / 4).collect();
+        let dir = fixture(&["yarn.lock"]);
+        let cwd = dir.path().join(deep);
+        fs::create_dir_all(&cwd).unwrap();
+
+        let actual = search_up(&cwd, &["yarn.lock"], &SearchOptions::default()).unwrap();
+        assert_eq!(actual, dir.path().join("yarn.lock"));
+    }
+
+    #[test]
+    fn search_up_past_cap() {
+        // Never created, so every ancestor is probed and popped in turn.
+        let deep: PathBuf = std::iter::repeat_n("d", MAX_ANCESTORS + 1).collect();
+        let cwd = Path::new("/nonexistent").join(deep);
+
+        let error = search_up(&cwd, &["yarn.lock"], &SearchOptions::default()).unwrap_err();
+        assert!(matches!(error, RootError::TooDeep(path) if path == cwd));
+    }
+
+    #[cfg(unix)]
+    #[test_case(FollowSymlinks::Always, Some("sandbox/package-lock.json") ; "always")]
+    #[test_case(FollowSymlinks::Never, None ; "never")]
+    #[test_case(FollowSymlinks::FilesOnly, None ; "files only")]
+    fn search_up_through_symlinked_parent(follow_symlinks: FollowSymlinks, expected: Option<&str>) {
+        let dir = fixture(&["outside/sub/index.js", "sandbox/package-lock.json"]);
+        // Canonicalize, as the temporary directory may itself be behind a symlink.
+        let base = dir.path().canonicalize().unwrap();
+        std::os::unix::fs::symlink(base.join("outside"), base.join("sandbox/link")).unwrap();
+
+        let cwd = base.join("sandbox/link/sub");
+        let options = SearchOptions {
+            follow_symlinks,
+            ..SearchOptions::default()
+        };
+        let actual = search_up(&cwd, &["yarn.lock", "package-lock.json"], &options);
+        match expected {
+            Some(expected) => assert_eq!(actual.unwrap(), base.join(expected)),
+            None => assert_eq!(
+                actual.unwrap_err().to_string(),
+                format!(
+                    "No workspace found in {} or any of its ancestors within {}",
+                    cwd.display(),
+                    base.join("sandbox/link").display()
+                )
+            ),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test_case(FollowSymlinks::Always ; "always")]
+    #[test_case(FollowSymlinks::Never ; "never")]
+    #[test_case(FollowSymlinks::FilesOnly ; "files only")]
+    fn search_up_under_symlinked_prefix(follow_symlinks: FollowSymlinks) {
+        // Like a project in /tmp on macOS, where /tmp links to /private/tmp.
+        let dir = fixture(&[
+            "private/tmp/project/yarn.lock",
+            "private/tmp/project/src/index.js",
+        ]);
+        let base = dir.path().canonicalize().unwrap();
+        std::os::unix::fs::symlink(base.join("private/tmp"), base.join("tmp")).unwrap();
+
+        let cwd = base.join("tmp/project/src");
+        let options = SearchOptions {
+            follow_symlinks,
+            ..SearchOptions::default()
+        };
+        let actual = search_up(&cwd, &["yarn.lock"], &options).unwrap();
+        assert_eq!(actual, base.join("tmp/project/yarn.lock"));
+    }
+
+    #[cfg(unix)]
+    #[test_case(FollowSymlinks::Always, "sandbox/yarn.lock" ; "always")]
+    #[test_case(FollowSymlinks::Never, "package-lock.json" ; "never")]
+    #[test_case(FollowSymlinks::FilesOnly, "sandbox/yarn.lock" ; "files only")]
+    fn search_up_symlinked_file(follow_symlinks: FollowSymlinks, expected: &str) {
+        let dir = fixture(&["package-lock.json", "outside/yarn.lock", "sandbox/index.js"]);
+        // Canonicalize, as the temporary directory may itself be behind a symlink.
+        let base = dir.path().canonicalize().unwrap();
+        std::os::unix::fs::symlink(
+            base.join("outside/yarn.lock"),
+            base.join("sandbox/yarn.lock"),
+        )
+        .unwrap();
+
+        let cwd = base.join("sandbox");
+        let options = SearchOptions {
+            follow_symlinks,
+            ..SearchOptions::default()
+        };
+        let actual = search_up(&cwd, &["yarn.lock", "package-lock.json"], &options).unwrap();
+        assert_eq!(actual, base.join(expected));
+    }
+}
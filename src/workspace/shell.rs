@@ -0,0 +1,40 @@
+use super::manager::Manager;
+use crate::env::PREFERRED_WORKSPACE_MANAGER;
+
+/// A shell to write configuration for, see [`Manager::export_snippet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl Manager {
+    /// The line that sets `PREFERRED_WORKSPACE_MANAGER` to this manager in `shell`, e.g.
+    /// `export PREFERRED_WORKSPACE_MANAGER=pnpm` to add to a `.bashrc`.
+    pub fn export_snippet(&self, shell: Shell) -> String {
+        let name = self.name();
+        match shell {
+            Shell::Bash | Shell::Zsh => format!("export {PREFERRED_WORKSPACE_MANAGER}={name}"),
+            Shell::Fish => format!("set -gx {PREFERRED_WORKSPACE_MANAGER} {name}"),
+            Shell::PowerShell => format!("$env:{PREFERRED_WORKSPACE_MANAGER} = \"{name}\""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(Shell::Bash, "export PREFERRED_WORKSPACE_MANAGER=pnpm" ; "bash")]
+    #[test_case(Shell::Zsh, "export PREFERRED_WORKSPACE_MANAGER=pnpm" ; "zsh")]
+    #[test_case(Shell::Fish, "set -gx PREFERRED_WORKSPACE_MANAGER pnpm" ; "fish")]
+    #[test_case(Shell::PowerShell, r#"$env:PREFERRED_WORKSPACE_MANAGER = "pnpm""# ; "powershell")]
+    fn export_snippet(shell: Shell, expected: &str) {
+        assert_eq!(Manager::Pnpm.export_snippet(shell), expected);
+    }
+}
@@ -0,0 +1,140 @@
+use std::{fs, io};
+
+use super::root::{Result, Root};
+
+impl Root {
+    /// Whether the lockfile has changed since `node_modules` was last installed.
+    ///
+    /// Returns `true` when `node_modules` is absent, and `false` for managers without
+    /// a lockfile of their own. A missing lockfile is reported as a `NotFound` error.
+    pub fn reinstall_needed(&self) -> Result<bool> {
+        let Some(lockfile) = self.manager().lockfile() else {
+            return Ok(false);
+        };
+        let lockfile = fs::metadata(self.path().join(lockfile))?.modified()?;
+
+        match fs::metadata(self.path().join("node_modules")) {
+            Ok(node_modules) => Ok(lockfile > node_modules.modified()?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(true),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Whether the root `package.json` has changed since the lockfile was last written,
+    /// e.g. because a dependency was added by hand without installing.
+    ///
+    /// Returns `true` when the lockfile is absent, since nothing was ever installed, and
+    /// `false` without a `package.json` or for managers without a lockfile of their own.
+    pub fn lockfile_outdated(&self) -> Result<bool> {
+        let Some(lockfile) = self.manager().lockfile() else {
+            return Ok(false);
+        };
+        let package_json = match fs::metadata(self.path().join("package.json")) {
+            Ok(package_json) => package_json.modified()?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(error) => return Err(error.into()),
+        };
+
+        match fs::metadata(self.path().join(lockfile)) {
+            Ok(lockfile) => Ok(package_json > lockfile.modified()?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(true),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        path::Path,
+        time::{Duration, SystemTime},
+    };
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn reinstall_needed_when_lockfile_is_newer() {
+        let dir = fixture(&["yarn.lock", "node_modules/.yarn-integrity"]);
+        let future = SystemTime::now() + Duration::from_secs(60);
+        set_modified(&dir.path().join("yarn.lock"), future);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.reinstall_needed().unwrap());
+    }
+
+    #[test]
+    fn reinstall_not_needed_when_node_modules_is_newer() {
+        let dir = fixture(&["yarn.lock", "node_modules/.yarn-integrity"]);
+        let past = SystemTime::now() - Duration::from_secs(60);
+        set_modified(&dir.path().join("yarn.lock"), past);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.reinstall_needed().unwrap());
+    }
+
+    #[test]
+    fn reinstall_needed_without_node_modules() {
+        let dir = fixture(&["package-lock.json"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.reinstall_needed().unwrap());
+    }
+
+    #[test]
+    fn reinstall_needed_without_lockfile() {
+        let dir = fixture(&["pnpm-workspace.yaml", "node_modules/.modules.yaml"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.reinstall_needed().unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn lockfile_outdated_when_package_json_is_newer() {
+        let dir = fixture(&["package.json", "package-lock.json"]);
+        let future = SystemTime::now() + Duration::from_secs(60);
+        set_modified(&dir.path().join("package.json"), future);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.lockfile_outdated().unwrap());
+    }
+
+    #[test]
+    fn lockfile_not_outdated_when_lockfile_is_newer() {
+        let dir = fixture(&["package.json", "package-lock.json"]);
+        let past = SystemTime::now() - Duration::from_secs(60);
+        set_modified(&dir.path().join("package.json"), past);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.lockfile_outdated().unwrap());
+    }
+
+    #[test]
+    fn lockfile_outdated_without_lockfile() {
+        let dir = fixture(&["pnpm-workspace.yaml", "package.json"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.lockfile_outdated().unwrap());
+    }
+
+    #[test]
+    fn lockfile_not_outdated_without_package_json() {
+        let dir = fixture(&["yarn.lock"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.lockfile_outdated().unwrap());
+    }
+
+    #[test]
+    fn reinstall_not_needed_for_orchestrator() {
+        let dir = fixture(&["lerna.json"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.reinstall_needed().unwrap());
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Read;
+
+use super::{
+    manager::{Manager, SEARCH_ORDER},
+    root::Result,
+};
+
+impl Manager {
+    /// Detects the manager of a packed project, such as the output of `npm pack`, from
+    /// the manager files directly under the archive's leading `package/` directory.
+    ///
+    /// The archive must already be decompressed, e.g. by wrapping a `.tgz` in a gzip decoder.
+    pub fn detect_in_tar<R: Read>(archive: R) -> Result<Option<Manager>> {
+        let mut found = Vec::new();
+        for entry in tar::Archive::new(archive).entries()? {
+            let entry = entry?;
+            let path = entry.path()?;
+            let Ok(file) = path.strip_prefix("package") else {
+                continue;
+            };
+            if file.components().count() == 1 {
+                found.extend(Manager::try_from(file).ok());
+            }
+        }
+
+        Ok(SEARCH_ORDER
+            .iter()
+            .copied()
+            .find(|manager| found.contains(manager)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn archive(files: &[&str]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for file in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(0);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, Path::new(file), std::io::empty())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn detect_in_tar() {
+        let archive = archive(&["package/package.json", "package/yarn.lock"]);
+        assert_eq!(
+            Manager::detect_in_tar(archive.as_slice()).unwrap(),
+            Some(Manager::Yarn)
+        );
+    }
+
+    #[test]
+    fn detect_in_tar_ignores_nested_files() {
+        let archive = archive(&[
+            "package/package.json",
+            "package/node_modules/foo/package-lock.json",
+            "yarn.lock",
+        ]);
+        assert_eq!(Manager::detect_in_tar(archive.as_slice()).unwrap(), None);
+    }
+
+    #[test]
+    fn detect_in_tar_uses_search_order() {
+        let archive = archive(&["package/yarn.lock", "package/lerna.json"]);
+        assert_eq!(
+            Manager::detect_in_tar(archive.as_slice()).unwrap(),
+            Some(Manager::Lerna)
+        );
+    }
+}
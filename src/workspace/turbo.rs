@@ -0,0 +1,91 @@
+use serde_json::Value;
+
+use super::{
+    json,
+    root::{Result, Root, RootError},
+};
+
+impl Root {
+    /// Whether there's a `turbo.json` at the root, meaning turbo runs the tasks.
+    ///
+    /// Turbo has no workspace file of its own and always runs on top of a package
+    /// manager's workspace, so any manager's root can have one. Detection never yields
+    /// turbo and [`Manager`] has no variant for it; it's reported by this instead.
+    ///
+    /// [`Manager`]: super::Manager
+    pub fn has_turbo(&self) -> Result<bool> {
+        Ok(self.path().join("turbo.json").try_exists()?)
+    }
+
+    /// The names of the tasks declared in the root `turbo.json`, sorted by name.
+    ///
+    /// Turbo 2 declares them under `tasks`, and earlier versions under `pipeline`. Empty
+    /// when there's no `turbo.json`; see [`Root::has_turbo`].
+    pub fn turbo_tasks(&self) -> Result<Vec<String>> {
+        let path = self.path().join("turbo.json");
+        let Some(turbo) = json::read(&path)? else {
+            return Ok(Vec::new());
+        };
+
+        let Some(tasks) = turbo.get("tasks").or_else(|| turbo.get("pipeline")) else {
+            return Ok(Vec::new());
+        };
+        let Value::Object(tasks) = tasks else {
+            return Err(RootError::Manager(format!(
+                "Expected an object of tasks in {}",
+                path.display()
+            )));
+        };
+        let mut tasks: Vec<_> = tasks.keys().cloned().collect();
+        tasks.sort();
+        Ok(tasks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test_case(
+        r#"{ "$schema": "https://turbo.build/schema.json", "tasks": { "build": { "dependsOn": ["^build"] }, "test": {} } }"#,
+        &["build", "test"] ;
+        "tasks"
+    )]
+    #[test_case(
+        r#"{ "pipeline": { "lint": {}, "build": { "outputs": ["dist/**"] } } }"#,
+        &["build", "lint"] ;
+        "pipeline"
+    )]
+    #[test_case(r#"{ "globalEnv": ["CI"] }"#, &[] ; "without tasks")]
+    fn turbo_tasks(turbo_json: &str, expected: &[&str]) {
+        let dir = fixture(&["pnpm-workspace.yaml"]);
+        write(dir.path(), "turbo.json", turbo_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.has_turbo().unwrap());
+        assert_eq!(root.turbo_tasks().unwrap(), expected);
+    }
+
+    #[test]
+    fn turbo_tasks_without_turbo_json() {
+        let dir = fixture(&["yarn.lock"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.has_turbo().unwrap());
+        assert!(root.turbo_tasks().unwrap().is_empty());
+    }
+
+    #[test_case(r#"{ "tasks": { "build": {} "#, false ; "invalid json")]
+    #[test_case(r#"{ "tasks": ["build"] }"#, true ; "tasks not an object")]
+    fn turbo_tasks_malformed(turbo_json: &str, manager_error: bool) {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), "turbo.json", turbo_json);
+
+        let root = Root::new(dir.path()).unwrap();
+        let error = root.turbo_tasks().unwrap_err();
+        assert_eq!(matches!(error, RootError::Manager(_)), manager_error);
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    fs, io,
+    ops::ControlFlow,
+    path::{Component, Path},
+};
+
+/// Whether any component of `path` is a `node_modules` directory, e.g. to skip vendored
+/// dependencies. A name merely containing `node_modules`, like `my_node_modules`, doesn't count.
+pub fn is_in_node_modules(path: &Path) -> bool {
+    path.components()
+        .any(|component| component == Component::Normal("node_modules".as_ref()))
+}
+
+/// Recursively visits every file below `dir`, skipping `node_modules` directories.
+///
+/// Symlinks are reported as files and never followed, so cycles can't occur.
+pub(crate) fn walk_files(dir: &Path, visit: &mut impl FnMut(&Path)) -> io::Result<()> {
+    walk_files_until(dir, &mut |path| {
+        visit(path);
+        ControlFlow::Continue(())
+    })
+}
+
+/// Like [`walk_files`], but stops as soon as `visit` breaks.
+pub(crate) fn walk_files_until(
+    dir: &Path,
+    visit: &mut impl FnMut(&Path) -> ControlFlow<()>,
+) -> io::Result<()> {
+    // Whether the walk finished or broke early makes no difference to the caller.
+    let _flow = walk(dir, visit)?;
+    Ok(())
+}
+
+fn walk(
+    dir: &Path,
+    visit: &mut impl FnMut(&Path) -> ControlFlow<()>,
+) -> io::Result<ControlFlow<()>> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let flow = if !entry.file_type()?.is_dir() {
+            visit(&path)
+        } else if !is_in_node_modules(Path::new(&entry.file_name())) {
+            // Only the entry's own name, so a walk started inside `node_modules` still runs.
+            walk(&path, visit)?
+        } else {
+            ControlFlow::Continue(())
+        };
+        if flow.is_break() {
+            return Ok(flow);
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    #[test_case("/repo/node_modules/foo/node_modules/bar/index.js", true ; "deeply nested")]
+    #[test_case("node_modules", true ; "bare component")]
+    #[test_case("/repo/my_node_modules/node_modules_backup/index.js", false ; "substring")]
+    #[test_case("/repo/packages/app/src/index.js", false ; "clean path")]
+    fn is_in_node_modules(path: &str, expected: bool) {
+        assert_eq!(super::is_in_node_modules(Path::new(path)), expected);
+    }
+
+    #[test]
+    fn walk_files_skips_node_modules() {
+        let dir = fixture(&[
+            "package.json",
+            "packages/a/package.json",
+            "node_modules/foo/package.json",
+            "packages/a/node_modules/bar/package.json",
+        ]);
+
+        let mut files = Vec::new();
+        walk_files(dir.path(), &mut |path| files.push(path.to_path_buf())).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                dir.path().join("package.json"),
+                dir.path().join("packages/a/package.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_files_until_stops_on_break() {
+        let dir = fixture(&["a/1.js", "a/2.js", "b/3.js"]);
+
+        let mut visited = 0;
+        walk_files_until(dir.path(), &mut |_path| {
+            visited += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert_eq!(visited, 1);
+    }
+}
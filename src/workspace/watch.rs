@@ -0,0 +1,50 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{Stream, channel::mpsc};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::root::{Result, Root};
+
+impl Root {
+    /// Returns a stream that yields whenever the primary manager file is modified.
+    ///
+    /// The root directory is watched rather than the file itself, so editors that
+    /// save atomically by renaming a temporary file over the original are still seen.
+    pub fn watch(&self) -> Result<impl Stream<Item = ()> + use<>> {
+        let file_name = self.manager().primary_file().as_os_str();
+        let (sender, receiver) = mpsc::unbounded();
+
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            let changed = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+            if changed && event.paths.iter().any(|p| p.file_name() == Some(file_name)) {
+                let _ = sender.unbounded_send(());
+            }
+        })?;
+        watcher.watch(self.path(), RecursiveMode::NonRecursive)?;
+
+        Ok(Watch {
+            _watcher: watcher,
+            receiver,
+        })
+    }
+}
+
+struct Watch {
+    // Dropping the watcher stops the stream, so it lives as long as the receiver.
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::UnboundedReceiver<()>,
+}
+
+impl Stream for Watch {
+    type Item = ();
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::root::{Result, Root};
+
+/// Detects the roots of the git repository whose main worktree is `repo` and of each of
+/// its linked worktrees, in that order, with linked worktrees sorted by path.
+///
+/// Linked worktrees are read from `.git/worktrees`, as `git worktree list` does, so git
+/// itself needn't be installed. Worktrees whose directory is gone are skipped, as
+/// `git worktree prune` would remove them.
+pub fn find_roots_in_worktrees(repo: &Path) -> Result<Vec<Root>> {
+    let mut linked = Vec::new();
+    let entries = match fs::read_dir(repo.join(".git/worktrees")) {
+        Ok(entries) => Some(entries),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error.into()),
+    };
+    for entry in entries.into_iter().flatten() {
+        if let Some(worktree) = linked_worktree(&entry?.path())? {
+            linked.push(worktree);
+        }
+    }
+    linked.sort();
+
+    let mut roots = vec![Root::new(repo)?];
+    for worktree in linked {
+        roots.push(Root::new(worktree)?);
+    }
+    Ok(roots)
+}
+
+/// The directory of the linked worktree administered from `admin`, such as
+/// `.git/worktrees/feature`, if it still exists.
+fn linked_worktree(admin: &Path) -> Result<Option<PathBuf>> {
+    // `gitdir` holds the path of the worktree's `.git` file, possibly relative to `admin`.
+    let gitdir = match fs::read_to_string(admin.join("gitdir")) {
+        Ok(gitdir) => gitdir,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let git_file = admin.join(gitdir.trim());
+
+    match git_file.parent() {
+        Some(worktree) if git_file.try_exists()? => Ok(Some(worktree.to_path_buf())),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        test_utils::{fixture, write},
+        workspace::Manager,
+    };
+
+    #[test]
+    fn find_roots_in_two_worktrees() {
+        let dir = fixture(&[
+            "main/.git/HEAD",
+            "main/yarn.lock",
+            "feature/pnpm-workspace.yaml",
+        ]);
+        let feature_git = dir.path().join("feature/.git");
+        write(
+            dir.path(),
+            "feature/.git",
+            &format!(
+                "gitdir: {}\n",
+                dir.path().join("main/.git/worktrees/feature").display()
+            ),
+        );
+        write(
+            dir.path(),
+            "main/.git/worktrees/feature/gitdir",
+            &format!("{}\n", feature_git.display()),
+        );
+        // Left behind by a worktree that was deleted without `git worktree remove`.
+        write(
+            dir.path(),
+            "main/.git/worktrees/gone/gitdir",
+            &format!("{}\n", dir.path().join("gone/.git").display()),
+        );
+
+        let roots: Vec<_> = find_roots_in_worktrees(&dir.path().join("main"))
+            .unwrap()
+            .into_iter()
+            .map(|root| (root.manager(), root.path().to_path_buf()))
+            .collect();
+        assert_eq!(
+            roots,
+            [
+                (Manager::Yarn, dir.path().join("main")),
+                (Manager::Pnpm, dir.path().join("feature")),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_roots_without_linked_worktrees() {
+        let dir = fixture(&[".git/HEAD", "package-lock.json"]);
+        let roots = find_roots_in_worktrees(dir.path()).unwrap();
+        assert_eq!(roots, [Root::new(dir.path()).unwrap()]);
+    }
+}
@@ -0,0 +1,14 @@
+use std::{fs, io, path::Path};
+
+use serde_yaml::Value;
+
+use super::root::Result;
+
+/// Reads and parses the YAML file at `path`, or `None` if there isn't one.
+pub(crate) fn read(path: &Path) -> Result<Option<Value>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(serde_yaml::from_str(&contents)?)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
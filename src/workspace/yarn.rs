@@ -0,0 +1,263 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde_yaml::Value;
+
+use super::{
+    manager::{Manager, VersionPolicy, YARN_PSEUDO_VERSIONS},
+    package_json,
+    root::{Result, Root},
+    yaml,
+};
+
+/// The generation of yarn, since classic yarn and Berry take different flags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YarnMode {
+    /// Yarn 1.x.
+    #[default]
+    Classic,
+    /// Yarn 2 and later.
+    Berry,
+}
+
+impl YarnMode {
+    /// The mode of a yarn version like `4.1.0`, or `None` if it has no major version.
+    ///
+    /// The `berry`, `stable`, and `canonical` pseudo-versions all mean Berry.
+    pub fn from_version(version: &str) -> Option<YarnMode> {
+        if YARN_PSEUDO_VERSIONS.contains(&version) {
+            return Some(YarnMode::Berry);
+        }
+
+        let major: u64 = version.split('.').next()?.parse().ok()?;
+        Some(if major >= 2 {
+            YarnMode::Berry
+        } else {
+            YarnMode::Classic
+        })
+    }
+}
+
+/// Reads and parses the Berry `.yarnrc.yml` in `dir`, or `None` if there isn't one.
+pub(crate) fn read_yarnrc_yml(dir: &Path) -> Result<Option<Value>> {
+    yaml::read(&dir.join(".yarnrc.yml"))
+}
+
+/// The value of `key` in the classic `.yarnrc` in `dir`, whose lines look like
+/// `yarn-offline-mirror "./npm-packages-offline-cache"`.
+fn read_yarnrc_value(dir: &Path, key: &str) -> Result<Option<String>> {
+    let yarnrc = match fs::read_to_string(dir.join(".yarnrc")) {
+        Ok(yarnrc) => yarnrc,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+
+    let value = yarnrc.lines().find_map(|line| {
+        let (name, value) = line.trim().split_once(char::is_whitespace)?;
+        (name.trim_matches('"') == key).then(|| value.trim().trim_matches('"').to_string())
+    });
+    Ok(value)
+}
+
+impl Root {
+    /// Which yarn the workspace uses, or `None` for other managers.
+    ///
+    /// A yarn version pinned by `packageManager` decides, and otherwise a `.yarnrc.yml`,
+    /// which only Berry reads, means Berry.
+    pub fn yarn_mode(&self) -> Result<Option<YarnMode>> {
+        if self.manager() != Manager::Yarn {
+            return Ok(None);
+        }
+
+        let package = package_json::read(self.path())?;
+        let spec = package
+            .as_ref()
+            .and_then(|package| package.get("packageManager"))
+            .and_then(serde_json::Value::as_str);
+        if let Some(spec) = spec
+            && let Ok((Manager::Yarn, Some(version))) =
+                Manager::from_corepack_spec_with(spec, VersionPolicy::AllowRanges)
+            && let Some(mode) = YarnMode::from_version(&version)
+        {
+            return Ok(Some(mode));
+        }
+
+        let berry = self.path().join(".yarnrc.yml").try_exists()?;
+        Ok(Some(if berry {
+            YarnMode::Berry
+        } else {
+            YarnMode::Classic
+        }))
+    }
+
+    /// Whether the workspace has an offline cache of its dependencies, so that yarn can
+    /// install without the network.
+    ///
+    /// That's Berry's cache folder, `.yarn/cache` unless `cacheFolder` in `.yarnrc.yml` says
+    /// otherwise, or the `yarn-offline-mirror` in classic yarn's `.yarnrc`. Always `false`
+    /// for other managers.
+    pub fn has_offline_cache(&self) -> Result<bool> {
+        if self.manager() != Manager::Yarn {
+            return Ok(false);
+        }
+
+        let yarnrc = read_yarnrc_yml(self.path())?;
+        let cache_folder = yarnrc
+            .as_ref()
+            .and_then(|yarnrc| yarnrc.get("cacheFolder"))
+            .and_then(Value::as_str)
+            .unwrap_or(".yarn/cache");
+        if self.path().join(cache_folder).is_dir() {
+            return Ok(true);
+        }
+
+        let mirror = read_yarnrc_value(self.path(), "yarn-offline-mirror")?;
+        Ok(mirror.is_some_and(|mirror| self.path().join(mirror).is_dir()))
+    }
+
+    /// The bundled yarn release configured by `yarnPath` in `.yarnrc.yml`, resolved
+    /// against the root. `None` for classic yarn, other managers, or when unset.
+    pub fn yarn_release_path(&self) -> Result<Option<PathBuf>> {
+        if self.manager() != Manager::Yarn {
+            return Ok(None);
+        }
+        let Some(yarnrc) = read_yarnrc_yml(self.path())? else {
+            return Ok(None);
+        };
+
+        let yarn_path = yarnrc.get("yarnPath").and_then(Value::as_str);
+        Ok(yarn_path.map(|yarn_path| self.path().join(yarn_path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::test_utils::{fixture, write};
+
+    #[test]
+    fn yarn_release_path() {
+        let dir = fixture(&["yarn.lock", ".yarn/releases/yarn-4.1.0.cjs"]);
+        write(
+            dir.path(),
+            ".yarnrc.yml",
+            "nodeLinker: node-modules\nyarnPath: .yarn/releases/yarn-4.1.0.cjs\n",
+        );
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(
+            root.yarn_release_path().unwrap(),
+            Some(dir.path().join(".yarn/releases/yarn-4.1.0.cjs"))
+        );
+    }
+
+    #[test]
+    fn yarn_release_path_unset() {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), ".yarnrc.yml", "nodeLinker: node-modules\n");
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.yarn_release_path().unwrap(), None);
+    }
+
+    #[test_case(&[], Some(YarnMode::Classic) ; "classic by default")]
+    #[test_case(&[(".yarnrc.yml", "nodeLinker: pnp\n")], Some(YarnMode::Berry) ; "yarnrc yml")]
+    #[test_case(
+        &[("package.json", r#"{ "packageManager": "yarn@4.1.0" }"#)],
+        Some(YarnMode::Berry) ;
+        "berry package manager"
+    )]
+    #[test_case(
+        &[("package.json", r#"{ "packageManager": "yarn@1.22.19" }"#), (".yarnrc.yml", "")],
+        Some(YarnMode::Classic) ;
+        "classic package manager"
+    )]
+    #[test_case(
+        &[("package.json", r#"{ "packageManager": "yarn@berry" }"#)],
+        Some(YarnMode::Berry) ;
+        "berry pseudo version"
+    )]
+    fn yarn_mode(files: &[(&str, &str)], expected: Option<YarnMode>) {
+        let dir = fixture(&["yarn.lock"]);
+        for (file, contents) in files {
+            write(dir.path(), file, contents);
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.yarn_mode().unwrap(), expected);
+    }
+
+    #[test_case("berry", Some(YarnMode::Berry) ; "berry")]
+    #[test_case("stable", Some(YarnMode::Berry) ; "stable")]
+    #[test_case("canonical", Some(YarnMode::Berry) ; "canonical")]
+    #[test_case("3.0.0", Some(YarnMode::Berry) ; "berry version")]
+    #[test_case("1.22.0", Some(YarnMode::Classic) ; "classic version")]
+    #[test_case("latest", None ; "unknown tag")]
+    fn from_version(version: &str, expected: Option<YarnMode>) {
+        assert_eq!(YarnMode::from_version(version), expected);
+    }
+
+    #[test]
+    fn yarn_mode_other_manager() {
+        let dir = fixture(&["pnpm-workspace.yaml", ".yarnrc.yml"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.yarn_mode().unwrap(), None);
+    }
+
+    #[test_case(&[".yarn/cache/lodash-npm-4.17.21-6382451519-eb835a2e51.zip"], &[] ; "berry cache")]
+    #[test_case(
+        &["offline/cache/lodash-npm-4.17.21.zip"],
+        &[(".yarnrc.yml", "cacheFolder: ./offline/cache\n")] ;
+        "berry cache folder"
+    )]
+    #[test_case(
+        &["npm-packages-offline-cache/lodash-4.17.21.tgz"],
+        &[(".yarnrc", "yarn-offline-mirror \"./npm-packages-offline-cache\"\nyarn-offline-mirror-pruning true\n")] ;
+        "classic mirror"
+    )]
+    fn has_offline_cache(files: &[&str], contents: &[(&str, &str)]) {
+        let dir = fixture(&["yarn.lock"]);
+        for file in files {
+            write(dir.path(), file, "");
+        }
+        for (file, contents) in contents {
+            write(dir.path(), file, contents);
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(root.has_offline_cache().unwrap());
+    }
+
+    #[test_case(&[] ; "nothing configured")]
+    #[test_case(&[(".yarnrc", "yarn-offline-mirror \"./missing\"\n")] ; "missing mirror")]
+    fn has_no_offline_cache(contents: &[(&str, &str)]) {
+        let dir = fixture(&["yarn.lock"]);
+        for (file, contents) in contents {
+            write(dir.path(), file, contents);
+        }
+
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.has_offline_cache().unwrap());
+    }
+
+    #[test]
+    fn has_offline_cache_other_manager() {
+        let dir = fixture(&["package-lock.json", ".yarn/cache/lodash.zip"]);
+        let root = Root::new(dir.path()).unwrap();
+        assert!(!root.has_offline_cache().unwrap());
+    }
+
+    #[test]
+    fn yarn_release_path_classic() {
+        let dir = fixture(&["yarn.lock"]);
+        write(dir.path(), ".yarnrc", "--install.frozen-lockfile true\n");
+
+        let root = Root::new(dir.path()).unwrap();
+        assert_eq!(root.yarn_release_path().unwrap(), None);
+    }
+}
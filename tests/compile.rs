@@ -0,0 +1,16 @@
+//! Checks that the public API compiles for the enabled features, as a downstream crate
+//! would use it.
+
+#[test]
+fn public_api_compiles() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/default.rs");
+    #[cfg(feature = "miette")]
+    cases.pass("tests/ui/miette.rs");
+    #[cfg(feature = "sysinfo")]
+    cases.pass("tests/ui/sysinfo.rs");
+    #[cfg(feature = "tar")]
+    cases.pass("tests/ui/tar.rs");
+    #[cfg(feature = "watch")]
+    cases.pass("tests/ui/watch.rs");
+}
@@ -0,0 +1,72 @@
+//! Exercises each cargo feature on its own and in combination. Run the matrix with e.g.
+//! `cargo test --features tar` or `cargo test --features miette,tar`.
+
+use js_workspace::workspace::{Manager, Root};
+
+/// Builds an npm-style tarball with empty files at `paths`.
+#[cfg(feature = "tar")]
+fn tarball(paths: &[&str]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for path in paths {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, std::io::empty())
+            .unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn default_features() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("yarn.lock"), "").unwrap();
+
+    assert_eq!(Root::new(dir.path()).unwrap().manager(), Manager::Yarn);
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn miette_reports_root_errors() {
+    use miette::Diagnostic;
+
+    let error = Root::from_manager_file("lolwut.json".as_ref()).unwrap_err();
+    assert_eq!(error.code().unwrap().to_string(), "js_workspace::manager");
+}
+
+#[cfg(feature = "sysinfo")]
+#[test]
+fn sysinfo_maps_process_names() {
+    assert_eq!(Manager::from_process_name("pnpm"), Some(Manager::Pnpm));
+    assert_eq!(Manager::from_process_name("cargo"), None);
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn tar_detects_manager() {
+    let archive = tarball(&["package/package.json", "package/yarn.lock"]);
+    let manager = Manager::detect_in_tar(archive.as_slice()).unwrap();
+    assert_eq!(manager, Some(Manager::Yarn));
+}
+
+#[cfg(all(feature = "miette", feature = "tar"))]
+#[test]
+fn tar_results_convert_into_reports() {
+    fn detect(archive: &[u8]) -> miette::Result<Option<Manager>> {
+        Ok(Manager::detect_in_tar(archive)?)
+    }
+
+    let archive = tarball(&["package/package.json", "package/rush.json"]);
+    assert_eq!(detect(&archive).unwrap(), Some(Manager::Rush));
+}
+
+#[cfg(all(feature = "miette", feature = "watch"))]
+#[test]
+fn watch_errors_are_diagnostics() {
+    use miette::Diagnostic;
+
+    let error = js_workspace::workspace::root::RootError::from(notify::Error::generic("boom"));
+    assert_eq!(error.code().unwrap().to_string(), "js_workspace::watch");
+}
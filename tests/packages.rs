@@ -0,0 +1,62 @@
+//! Reads the packages of a workspace laid out on disk as a downstream crate would.
+
+use std::{fs, path::Path};
+
+use indexmap::IndexMap;
+use js_workspace::workspace::{Package, Root};
+
+fn write(dir: &Path, file: &str, contents: &str) {
+    let path = dir.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn packages_detailed_parses_package_json_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "yarn.lock", "");
+    write(
+        dir.path(),
+        "package.json",
+        r#"{ "private": true, "workspaces": ["packages/*"] }"#,
+    );
+    write(
+        dir.path(),
+        "packages/app/package.json",
+        r#"{
+            "name": "@acme/app",
+            "version": "1.2.3",
+            "private": true,
+            "scripts": { "test": "vitest", "build": "tsc -b" }
+        }"#,
+    );
+    write(dir.path(), "packages/bare/package.json", "{}");
+
+    let root = Root::new(dir.path()).unwrap();
+    let packages = root.packages_detailed().unwrap();
+    // Maps compare equal regardless of order, so the declaration order is checked separately.
+    let names: Vec<_> = packages[0].scripts.keys().collect();
+    assert_eq!(names, ["test", "build"]);
+    assert_eq!(
+        packages,
+        [
+            Package {
+                path: dir.path().join("packages/app"),
+                name: Some(String::from("@acme/app")),
+                version: Some(String::from("1.2.3")),
+                private: true,
+                scripts: IndexMap::from([
+                    (String::from("test"), String::from("vitest")),
+                    (String::from("build"), String::from("tsc -b")),
+                ]),
+            },
+            Package {
+                path: dir.path().join("packages/bare"),
+                name: None,
+                version: None,
+                private: false,
+                scripts: IndexMap::new(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,14 @@
+use std::path::Path;
+
+use js_workspace::workspace::{FollowSymlinks, Manager, Root, root::RootError};
+
+fn discover(cwd: &Path) -> Result<(Manager, &'static str), RootError> {
+    let root = Root::builder()
+        .follow_symlinks(FollowSymlinks::Never)
+        .discover(cwd)?;
+    Ok((root.manager(), root.manager().binary()))
+}
+
+fn main() {
+    let _ = discover(Path::new("."));
+}
@@ -0,0 +1,6 @@
+use js_workspace::workspace::Root;
+
+fn main() -> miette::Result<()> {
+    let _ = Root::new(".").map_err(miette::Report::new);
+    Ok(())
+}
@@ -0,0 +1,6 @@
+use js_workspace::workspace::Manager;
+
+fn main() {
+    let manager: Option<Manager> = Manager::from_parent_process();
+    let _ = manager.or_else(|| Manager::from_process_name("pnpm"));
+}
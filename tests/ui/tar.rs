@@ -0,0 +1,6 @@
+use js_workspace::workspace::{Manager, root::RootError};
+
+fn main() {
+    let result: Result<Option<Manager>, RootError> = Manager::detect_in_tar(std::io::empty());
+    let _ = result;
+}
@@ -0,0 +1,12 @@
+use futures::Stream;
+use js_workspace::workspace::{Root, root::RootError};
+
+fn watch(root: &Root) -> Result<impl Stream<Item = ()>, RootError> {
+    root.watch()
+}
+
+fn main() {
+    if let Ok(root) = Root::new(".") {
+        let _ = watch(&root);
+    }
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "watch")]
+
+use std::{fs, thread, time::Duration};
+
+use futures::{StreamExt, channel::oneshot, executor::block_on, future};
+use js_workspace::workspace::Root;
+
+/// Waits for the next item from `stream`, giving up after a few seconds.
+fn next_event(stream: &mut (impl futures::Stream<Item = ()> + Unpin)) -> Option<()> {
+    let (sender, timeout) = oneshot::channel::<()>();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(5));
+        let _ = sender.send(());
+    });
+
+    block_on(async {
+        match future::select(stream.next(), timeout).await {
+            future::Either::Left((event, _)) => event,
+            future::Either::Right(_) => None,
+        }
+    })
+}
+
+#[test]
+fn watch_emits_when_lockfile_is_modified() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = dir.path().join("yarn.lock");
+    fs::write(&lockfile, "").unwrap();
+
+    let root = Root::new(dir.path()).unwrap();
+    let mut events = Box::pin(root.watch().unwrap());
+
+    fs::write(&lockfile, "# yarn lockfile v1\n").unwrap();
+    assert_eq!(next_event(&mut events), Some(()));
+}
+
+#[test]
+fn watch_emits_on_atomic_save() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = dir.path().join("yarn.lock");
+    fs::write(&lockfile, "").unwrap();
+
+    let root = Root::new(dir.path()).unwrap();
+    let mut events = Box::pin(root.watch().unwrap());
+
+    let temporary = dir.path().join(".yarn.lock.swp");
+    fs::write(&temporary, "# yarn lockfile v1\n").unwrap();
+    fs::rename(&temporary, &lockfile).unwrap();
+    assert_eq!(next_event(&mut events), Some(()));
+}